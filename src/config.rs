@@ -33,4 +33,8 @@ pub struct Woo {
     pub cs: String,
     /// Host URL
     pub host: String,
+    /// WordPress user name, for WordPress endpoints like media upload
+    pub wp_user: Option<String>,
+    /// WordPress application password of the user
+    pub wp_password: Option<String>,
 }
@@ -1,6 +1,14 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde_with::skip_serializing_none;
+use tokio::task::JoinSet;
 
-use crate::{DiscountType, MetaData};
+use crate::{
+    controllers::Entity, ApiClient, CodePattern, Coupon, DiscountType, GeneratedCoupons, ListQuery,
+    MetaBag, MetaData,
+};
 #[skip_serializing_none]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 pub struct CreateCoupon {
@@ -8,8 +16,7 @@ pub struct CreateCoupon {
     discount_type: DiscountType,
     amount: String,
     description: Option<String>,
-    date_expires: Option<String>,
-    date_expires_gmt: Option<String>,
+    date_expires_gmt: Option<NaiveDateTime>,
     individual_use: bool,
     product_ids: Option<Vec<i32>>,
     excluded_product_ids: Option<Vec<i32>>,
@@ -48,8 +55,7 @@ pub struct CreateCouponBuilder<C, D, A> {
     discount_type: D,
     amount: A,
     description: Option<String>,
-    date_expires: Option<String>,
-    date_expires_gmt: Option<String>,
+    date_expires_gmt: Option<NaiveDateTime>,
     individual_use: bool,
     product_ids: Option<Vec<i32>>,
     excluded_product_ids: Option<Vec<i32>>,
@@ -85,7 +91,6 @@ impl<C, D, A> CreateCouponBuilder<C, D, A> {
             discount_type: self.discount_type,
             amount: self.amount,
             description: self.description,
-            date_expires: self.date_expires,
             date_expires_gmt: self.date_expires_gmt,
             individual_use: self.individual_use,
             product_ids: self.product_ids,
@@ -113,7 +118,6 @@ impl<C, D, A> CreateCouponBuilder<C, D, A> {
             discount_type: WithDiscountType(discount_type),
             amount: self.amount,
             description: self.description,
-            date_expires: self.date_expires,
             date_expires_gmt: self.date_expires_gmt,
             individual_use: self.individual_use,
             product_ids: self.product_ids,
@@ -138,7 +142,6 @@ impl<C, D, A> CreateCouponBuilder<C, D, A> {
             discount_type: self.discount_type,
             amount: WithAmount(amount.into()),
             description: self.description,
-            date_expires: self.date_expires,
             date_expires_gmt: self.date_expires_gmt,
             individual_use: self.individual_use,
             product_ids: self.product_ids,
@@ -161,14 +164,9 @@ impl<C, D, A> CreateCouponBuilder<C, D, A> {
         let _ = self.description.insert(description.into());
         self
     }
-    /// The date the coupon expires, in the site's timezone. Format: YEAR-MONTH-DAY. Example: "2024-01-31"
-    pub fn date_expires(mut self, date_expires: impl Into<String>) -> Self {
-        let _ = self.date_expires.insert(date_expires.into());
-        self
-    }
-    /// The date the coupon expires, as GMT.Format: YEAR-MONTH-DAY. Example: "2024-01-31"
-    pub fn date_expires_gmt(mut self, date_expires_gmt: impl Into<String>) -> Self {
-        let _ = self.date_expires_gmt.insert(date_expires_gmt.into());
+    /// The date the coupon expires, sent as GMT.
+    pub fn date_expires(mut self, date_expires: DateTime<Utc>) -> Self {
+        let _ = self.date_expires_gmt.insert(date_expires.naive_utc());
         self
     }
     /// If true, the coupon can only be used individually. Other applied coupons will be removed from the cart. Default is false.
@@ -253,6 +251,11 @@ impl<C, D, A> CreateCouponBuilder<C, D, A> {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
 }
 impl CreateCouponBuilder<WithCode, WithDiscountType, WithAmount> {
     pub fn build(self) -> CreateCoupon {
@@ -261,7 +264,6 @@ impl CreateCouponBuilder<WithCode, WithDiscountType, WithAmount> {
             discount_type: self.discount_type.0,
             amount: self.amount.0,
             description: self.description,
-            date_expires: self.date_expires,
             date_expires_gmt: self.date_expires_gmt,
             individual_use: self.individual_use,
             product_ids: self.product_ids,
@@ -288,8 +290,7 @@ pub struct UpdateCoupon {
     discount_type: Option<DiscountType>,
     amount: Option<String>,
     description: Option<String>,
-    date_expires: Option<String>,
-    date_expires_gmt: Option<String>,
+    date_expires_gmt: Option<NaiveDateTime>,
     individual_use: Option<bool>,
     product_ids: Option<Vec<i32>>,
     excluded_product_ids: Option<Vec<i32>>,
@@ -317,8 +318,7 @@ pub struct UpdateCouponBuilder {
     discount_type: Option<DiscountType>,
     amount: Option<String>,
     description: Option<String>,
-    date_expires: Option<String>,
-    date_expires_gmt: Option<String>,
+    date_expires_gmt: Option<NaiveDateTime>,
     individual_use: Option<bool>,
     product_ids: Option<Vec<i32>>,
     excluded_product_ids: Option<Vec<i32>>,
@@ -360,14 +360,9 @@ impl UpdateCouponBuilder {
         let _ = self.description.insert(description.into());
         self
     }
-    /// The date the coupon expires, in the site's timezone. Format: YEAR-MONTH-DAY. Example: "2024-01-31"
-    pub fn date_expires(&mut self, date_expires: impl Into<String>) -> &mut Self {
-        let _ = self.date_expires.insert(date_expires.into());
-        self
-    }
-    /// The date the coupon expires, as GMT.Format: YEAR-MONTH-DAY. Example: "2024-01-31"
-    pub fn date_expires_gmt(&mut self, date_expires_gmt: impl Into<String>) -> &mut Self {
-        let _ = self.date_expires_gmt.insert(date_expires_gmt.into());
+    /// The date the coupon expires, sent as GMT.
+    pub fn date_expires(&mut self, date_expires: DateTime<Utc>) -> &mut Self {
+        let _ = self.date_expires_gmt.insert(date_expires.naive_utc());
         self
     }
     /// If true, the coupon can only be used individually. Other applied coupons will be removed from the cart. Default is false.
@@ -452,6 +447,11 @@ impl UpdateCouponBuilder {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(&mut self, meta_bag: &MetaBag) -> &mut Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
     pub fn build(&self) -> UpdateCoupon {
         UpdateCoupon {
             id: self.id,
@@ -459,8 +459,7 @@ impl UpdateCouponBuilder {
             discount_type: self.discount_type.clone(),
             amount: self.amount.clone(),
             description: self.description.clone(),
-            date_expires: self.date_expires.clone(),
-            date_expires_gmt: self.date_expires_gmt.clone(),
+            date_expires_gmt: self.date_expires_gmt,
             individual_use: self.individual_use,
             product_ids: self.product_ids.clone(),
             excluded_product_ids: self.excluded_product_ids.clone(),
@@ -478,3 +477,144 @@ impl UpdateCouponBuilder {
         }
     }
 }
+impl ApiClient {
+    /// This API creates coupons with unique random codes from a template, e.g. single use
+    /// coupons of a campaign. The code of the template is replaced, generated codes are checked
+    /// against existing coupons before they are created.
+    /// Coupons are created 100 at a time, one batch after another. Returns the created coupons
+    /// and the codes that failed, including the ones of batches whose request failed,
+    /// and fails if none could be created.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, CodePattern, Config, Coupon, DiscountType};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let template = Coupon::create()
+    ///         .code("template")
+    ///         .discount_type(DiscountType::Percent)
+    ///         .amount("15")
+    ///         .usage_limit(1)
+    ///         .build();
+    ///     let pattern = CodePattern::new().prefix("SUMMER-").length(8).checksum();
+    ///     let generated = client.generate_coupons(template, 1000, pattern).await?;
+    ///     tokio::fs::write("coupons.csv", generated.csv()).await?;
+    ///     info!("Created {} coupons", generated.created.len());
+    ///     for code in &generated.failed {
+    ///         info!("Failed to create {code}");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn generate_coupons(
+        &self,
+        template: CreateCoupon,
+        count: usize,
+        pattern: CodePattern,
+    ) -> Result<GeneratedCoupons> {
+        if pattern.is_empty() {
+            return Err(anyhow!("Code pattern has no characters"));
+        }
+        if pattern
+            .capacity()
+            .is_some_and(|capacity| capacity < (count as u64).saturating_mul(2))
+        {
+            return Err(anyhow!(
+                "Code pattern is too short for {count} unique codes"
+            ));
+        }
+        // Codes by their lowercase form, WooCommerce doesn't tell codes apart by case.
+        let mut codes = BTreeMap::new();
+        let mut tries = 0;
+        while codes.len() < count {
+            tries += 1;
+            if tries > 5 {
+                return Err(anyhow!(
+                    "Only {} of {count} codes are unique, use a longer code pattern",
+                    codes.len()
+                ));
+            }
+            let mut candidates = BTreeMap::new();
+            while codes.len() + candidates.len() < count {
+                let code = pattern.generate();
+                let key = code.to_lowercase();
+                if !codes.contains_key(&key) {
+                    candidates.insert(key, code);
+                }
+            }
+            let taken = self.existing_coupon_codes(candidates.keys()).await?;
+            codes.extend(
+                candidates
+                    .into_iter()
+                    .filter(|(key, _)| !taken.contains(key)),
+            );
+        }
+        let create = codes
+            .into_values()
+            .map(|code| CreateCoupon {
+                code,
+                ..template.clone()
+            })
+            .collect::<Vec<_>>();
+        let uri = self.base_url.join(&Coupon::endpoint())?.join("batch")?;
+        let results = self
+            .batch_create_each::<Coupon, _>(uri, create.clone())
+            .await;
+        let mut created = vec![];
+        let mut failed = vec![];
+        let mut errors = vec![];
+        for (coupon, result) in create.into_iter().zip(results) {
+            match result {
+                Ok(coupon) => created.push(coupon),
+                Err(error) => {
+                    failed.push(coupon.code);
+                    errors.push(error);
+                }
+            }
+        }
+        if created.is_empty() && !failed.is_empty() {
+            return Err(anyhow!(
+                "Failed to create {} coupons: {}",
+                failed.len(),
+                errors[0]
+            ));
+        }
+        Ok(GeneratedCoupons { created, failed })
+    }
+    /// Codes that are already used by a coupon, in lowercase.
+    async fn existing_coupon_codes(
+        &self,
+        codes: impl Iterator<Item = &String>,
+    ) -> Result<BTreeSet<String>> {
+        let codes = codes.cloned().collect::<Vec<_>>();
+        let mut taken = BTreeSet::new();
+        for chunk in codes.chunks(10) {
+            let mut set = JoinSet::new();
+            for code in chunk {
+                let client = self.clone();
+                let code = code.to_owned();
+                set.spawn(async move {
+                    let query = ListQuery::new().param("code", &code);
+                    client
+                        .list_all_with_query::<Coupon>(&query)
+                        .await
+                        .map(|found| (code, found))
+                });
+            }
+            while let Some(joined) = set.join_next().await {
+                let (code, found) = joined??;
+                if found.iter().any(|c| c.code.eq_ignore_ascii_case(&code)) {
+                    taken.insert(code.to_lowercase());
+                }
+            }
+        }
+        Ok(taken)
+    }
+}
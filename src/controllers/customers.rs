@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use crate::{Billing, MetaData, Shipping};
+use anyhow::Result;
+
+use crate::{ApiClient, Billing, CustomerDownload, MetaBag, MetaData, Shipping};
 
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -161,6 +163,11 @@ impl CreateCustomer {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
 }
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -324,4 +331,39 @@ impl UpdateCustomer {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
+}
+impl ApiClient {
+    /// This API lists all the downloadable files a customer has access to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use chrono::Utc;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let downloads = client.customer_downloads(25).await?;
+    ///     for download in downloads.iter().filter(|d| d.is_available(Utc::now())) {
+    ///         info!(
+    ///             "{}: {} downloads remaining",
+    ///             download.download_name, download.downloads_remaining
+    ///         );
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn customer_downloads(&self, customer_id: i32) -> Result<Vec<CustomerDownload>> {
+        self.list_all_subentities(customer_id).await
+    }
 }
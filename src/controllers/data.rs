@@ -1 +1,150 @@
+use anyhow::Result;
 
+use crate::{controllers::Entity, ApiClient, Continent, Country, Currency, Geo};
+
+impl ApiClient {
+    /// This API lists all continents with their countries, states and currency formats.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let continents = client.continents().await?;
+    ///     for continent in continents {
+    ///         info!("{}: {} countries", continent.name, continent.countries.len());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn continents(&self) -> Result<Vec<Continent>> {
+        let uri = self.base_url.join(&Continent::endpoint())?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lists all countries with their states.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let countries = client.countries().await?;
+    ///     info!("Got {} countries", countries.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn countries(&self) -> Result<Vec<Country>> {
+        let uri = self.base_url.join(&Country::endpoint())?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lets you retrieve a country by ISO3166 alpha-2 code.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let country = client.country("BR").await?;
+    ///     info!("{} has {} states", country.name, country.states.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn country(&self, code: &str) -> Result<Country> {
+        let uri = self
+            .base_url
+            .join(&Country::endpoint())?
+            .join(&code.to_lowercase())?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lists all currencies.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let currencies = client.currencies().await?;
+    ///     info!("Got {} currencies", currencies.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn currencies(&self) -> Result<Vec<Currency>> {
+        let uri = self.base_url.join(&Currency::endpoint())?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lets you retrieve the store currency.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let currency = client.current_currency().await?;
+    ///     info!("Store currency: {}", currency.name);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn current_currency(&self) -> Result<Currency> {
+        let uri = self.base_url.join(&Currency::endpoint())?.join("current")?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API builds a [`Geo`] index from the continents and currencies.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let geo = client.geo().await?;
+    ///     info!("CA is a valid US state: {}", geo.is_valid_state("US", "CA"));
+    ///     info!("Currency format in Germany: {:?}", geo.currency_format("DE"));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn geo(&self) -> Result<Geo> {
+        let (continents, currencies) = tokio::join!(self.continents(), self.currencies());
+        Ok(Geo::new(continents?, currencies?))
+    }
+}
@@ -1,11 +1,12 @@
 use anyhow::{anyhow, Result};
-use serde::Serialize;
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
 use tokio::task::JoinSet;
 use url::Url;
 
-use crate::{ApiClient, BatchObject};
+use crate::{ApiClient, BatchObject, WooError};
 
-use super::Entity;
+use super::{query::ListQuery, Entity};
 
 const BATCH: &str = "batch";
 
@@ -38,21 +39,22 @@ impl ApiClient {
         self.get_request_with_tries(uri, 3).await
     }
 
-    async fn get_request_with_tries<T: Entity>(&self, uri: Url, tries: i32) -> Result<T> {
+    pub(crate) async fn get_request_with_tries<T: DeserializeOwned>(
+        &self,
+        uri: Url,
+        tries: i32,
+    ) -> Result<T> {
         for i in 1..tries {
             tracing::debug!("Connecting {uri}, try {i}");
-            match self
+            let response = self
                 .client
                 .get(uri.clone())
                 .basic_auth(self.ck(), Some(self.cs()))
                 .send()
-                .await?
-                .json::<T>()
-                .await
-            {
-                Ok(r) => {
-                    return Ok(r);
-                }
+                .await?;
+            let status = response.status();
+            match response.text().await {
+                Ok(body) => return decode(&uri, status, &body),
                 Err(e) => {
                     tracing::error!(
                         "Failed to connect to {uri} with error: {e}\n{} tries left",
@@ -88,12 +90,37 @@ impl ApiClient {
     /// }
     /// ```
     pub async fn list_all<T: Entity>(&self) -> Result<Vec<T>> {
+        self.list_all_with_query(&ListQuery::default()).await
+    }
+    /// This API helps you to view all entities of type T matching the query.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use chrono::{Duration, Utc};
+    /// use rust_woocommerce::{ApiClient, Config, ListQuery, Order};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let query = ListQuery::new().after(Utc::now() - Duration::days(7));
+    ///     let orders = client.list_all_with_query::<Order>(&query).await?;
+    ///     info!("Got {} orders for the last week", orders.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn list_all_with_query<T: Entity>(&self, query: &ListQuery) -> Result<Vec<T>> {
         let uri = self.base_url.join(&T::endpoint())?;
         let mut result = Vec::new();
         let mut set = JoinSet::new();
         let total_response = self
             .client
             .get(uri.clone())
+            .query(query.params())
             .basic_auth(&self.ck(), Some(self.cs()))
             .send()
             .await?;
@@ -110,19 +137,20 @@ impl ApiClient {
             let ck = self.ck();
             let cs = Some(self.cs());
             let url = uri.clone();
+            let params = query.params().to_vec();
             set.spawn(async move {
-                client
-                    .get(url)
-                    .query(&[("page", page), ("per_page", per_page)])
-                    .basic_auth(ck, cs)
-                    .send()
-                    .await?
-                    .json::<Vec<T>>()
-                    .await
+                fetch::<Vec<T>>(
+                    client
+                        .get(url)
+                        .query(&params)
+                        .query(&[("page", page), ("per_page", per_page)])
+                        .basic_auth(ck, cs),
+                )
+                .await
             });
         }
-        while let Some(Ok(Ok(v))) = set.join_next().await {
-            result.extend(v)
+        while let Some(joined) = set.join_next().await {
+            result.extend(joined??)
         }
         Ok(result)
     }
@@ -169,7 +197,7 @@ impl ApiClient {
         self.post_request_with_tries(&object, uri, 3).await
     }
 
-    async fn post_request_with_tries<T: Entity, O: Serialize + Sized>(
+    pub(crate) async fn post_request_with_tries<T: DeserializeOwned, O: Serialize + Sized>(
         &self,
         object: &O,
         uri: Url,
@@ -177,19 +205,16 @@ impl ApiClient {
     ) -> Result<T> {
         for i in 1..tries {
             tracing::debug!("Connecting {uri}, try {i}");
-            match self
+            let response = self
                 .client
                 .post(uri.clone())
                 .basic_auth(self.ck(), Some(self.cs()))
                 .json(&object)
                 .send()
-                .await?
-                .json::<T>()
-                .await
-            {
-                Ok(r) => {
-                    return Ok(r);
-                }
+                .await?;
+            let status = response.status();
+            match response.text().await {
+                Ok(body) => return decode(&uri, status, &body),
                 Err(e) => {
                     tracing::error!(
                         "Failed to connect to {uri} with error: {e}\n{} tries left",
@@ -236,7 +261,7 @@ impl ApiClient {
         self.put_request_with_tries(&object, uri, 3).await
     }
 
-    async fn put_request_with_tries<T: Entity, O: Serialize + Sized>(
+    pub(crate) async fn put_request_with_tries<T: DeserializeOwned, O: Serialize + Sized>(
         &self,
         object: &O,
         uri: Url,
@@ -244,17 +269,16 @@ impl ApiClient {
     ) -> Result<T> {
         for i in 1..tries {
             tracing::debug!("Connecting {uri}, try {i}");
-            match self
+            let response = self
                 .client
                 .put(uri.clone())
                 .basic_auth(self.ck(), Some(self.cs()))
                 .json(&object)
                 .send()
-                .await?
-                .json::<T>()
-                .await
-            {
-                Ok(r) => return Ok(r),
+                .await?;
+            let status = response.status();
+            match response.text().await {
+                Ok(body) => return decode(&uri, status, &body),
                 Err(e) => {
                     tracing::error!(
                         "Failed to connect to {uri} with error: {e}\n{} tries left",
@@ -297,22 +321,23 @@ impl ApiClient {
         self.delete_request_with_tries(uri, 3).await
     }
 
-    async fn delete_request_with_tries<T: Entity>(&self, uri: Url, tries: i32) -> Result<T> {
+    pub(crate) async fn delete_request_with_tries<T: DeserializeOwned>(
+        &self,
+        uri: Url,
+        tries: i32,
+    ) -> Result<T> {
         for i in 1..tries {
             tracing::debug!("Connecting {uri}, try {i}");
-            match self
+            let response = self
                 .client
                 .delete(uri.clone())
                 .basic_auth(self.ck(), Some(self.cs()))
                 .query(&[("force", true)])
                 .send()
-                .await?
-                .json::<T>()
-                .await
-            {
-                Ok(r) => {
-                    return Ok(r);
-                }
+                .await?;
+            let status = response.status();
+            match response.text().await {
+                Ok(body) => return decode(&uri, status, &body),
                 Err(e) => {
                     tracing::error!(
                         "Failed to connect to {uri} with error: {e}\n{} tries left",
@@ -383,18 +408,11 @@ impl ApiClient {
             let cs = Some(self.cs());
             let url = uri.clone();
             set.spawn(async move {
-                client
-                    .post(url)
-                    .basic_auth(ck, cs)
-                    .json(&batch)
-                    .send()
-                    .await?
-                    .json::<BatchObject<T>>()
-                    .await
+                fetch::<BatchObject<T>>(client.post(url).basic_auth(ck, cs).json(&batch)).await
             });
         }
-        while let Some(Ok(Ok(v))) = set.join_next().await {
-            result.extend(v.create)
+        while let Some(joined) = set.join_next().await {
+            result.extend(joined??.create)
         }
         Ok(result.into_iter().flatten().collect::<Vec<_>>())
     }
@@ -438,18 +456,11 @@ impl ApiClient {
             let cs = Some(self.cs());
             let url = uri.clone();
             set.spawn(async move {
-                client
-                    .post(url)
-                    .basic_auth(ck, cs)
-                    .json(&batch)
-                    .send()
-                    .await?
-                    .json::<BatchObject<T>>()
-                    .await
+                fetch::<BatchObject<T>>(client.post(url).basic_auth(ck, cs).json(&batch)).await
             });
         }
-        while let Some(Ok(Ok(v))) = set.join_next().await {
-            result.extend(v.update)
+        while let Some(joined) = set.join_next().await {
+            result.extend(joined??.update)
         }
         Ok(result.into_iter().flatten().collect::<Vec<_>>())
     }
@@ -486,19 +497,18 @@ impl ApiClient {
             let cs = Some(self.cs());
             let url = uri.clone();
             set.spawn(async move {
-                client
-                    .post(url)
-                    .basic_auth(ck, cs)
-                    .query(&[("force", true)])
-                    .json(&batch)
-                    .send()
-                    .await?
-                    .json::<BatchObject<T>>()
-                    .await
+                fetch::<BatchObject<T>>(
+                    client
+                        .post(url)
+                        .basic_auth(ck, cs)
+                        .query(&[("force", true)])
+                        .json(&batch),
+                )
+                .await
             });
         }
-        while let Some(Ok(Ok(v))) = set.join_next().await {
-            result.extend(v.delete)
+        while let Some(joined) = set.join_next().await {
+            result.extend(joined??.delete)
         }
         Ok(result.into_iter().flatten().collect::<Vec<_>>())
     }
@@ -560,32 +570,33 @@ impl ApiClient {
     /// ```
     pub async fn list_all_subentities<T: Entity>(&self, entity_id: i32) -> Result<Vec<T>> {
         let uri = self.base_url.join(&T::child_endpoint(entity_id))?;
-
-        for i in 1..3 {
-            tracing::debug!("Connecting {uri}, try {i}");
-            match self
-                .client
-                .get(uri.clone())
-                .basic_auth(self.ck(), Some(self.cs()))
-                .send()
-                .await?
-                .json::<Vec<T>>()
-                .await
-            {
-                Ok(r) => return Ok(r),
-                Err(e) => {
-                    tracing::error!(
-                        "Failed to connect to {uri} with error: {e}\n{} tries left",
-                        3 - i
-                    );
-                    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-                    continue;
-                }
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// Subentities of a paginated endpoint, e.g. variations or refunds, page by page
+    /// until a short page comes back. `params` are added to every page request.
+    pub(crate) async fn list_all_subentities_paged<T: Entity>(
+        &self,
+        entity_id: i32,
+        params: &[(&str, &str)],
+    ) -> Result<Vec<T>> {
+        let uri = self.base_url.join(&T::child_endpoint(entity_id))?;
+        let per_page = 100;
+        let mut result = Vec::new();
+        for page in 1.. {
+            let mut page_uri = uri.clone();
+            page_uri
+                .query_pairs_mut()
+                .extend_pairs(params)
+                .append_pair("page", &page.to_string())
+                .append_pair("per_page", &per_page.to_string());
+            let entities: Vec<T> = self.get_request_with_tries(page_uri, 3).await?;
+            let count = entities.len();
+            result.extend(entities);
+            if count < per_page {
+                break;
             }
         }
-        Err(anyhow!(
-            "Error retrieving subentitity for entity with id: {entity_id}"
-        ))
+        Ok(result)
     }
     /// This API helps you create a new subentity.
     ///
@@ -747,18 +758,11 @@ impl ApiClient {
             let cs = Some(self.cs());
             let url = uri.clone();
             set.spawn(async move {
-                client
-                    .post(url)
-                    .basic_auth(ck, cs)
-                    .json(&batch)
-                    .send()
-                    .await?
-                    .json::<BatchObject<T>>()
-                    .await
+                fetch::<BatchObject<T>>(client.post(url).basic_auth(ck, cs).json(&batch)).await
             });
         }
-        while let Some(Ok(Ok(v))) = set.join_next().await {
-            result.extend(v.create)
+        while let Some(joined) = set.join_next().await {
+            result.extend(joined??.create)
         }
         Ok(result.into_iter().flatten().collect::<Vec<_>>())
     }
@@ -808,18 +812,11 @@ impl ApiClient {
             let cs = Some(self.cs());
             let url = uri.clone();
             set.spawn(async move {
-                client
-                    .post(url)
-                    .basic_auth(ck, cs)
-                    .json(&batch)
-                    .send()
-                    .await?
-                    .json::<BatchObject<T>>()
-                    .await
+                fetch::<BatchObject<T>>(client.post(url).basic_auth(ck, cs).json(&batch)).await
             });
         }
-        while let Some(Ok(Ok(v))) = set.join_next().await {
-            result.extend(v.update)
+        while let Some(joined) = set.join_next().await {
+            result.extend(joined??.update)
         }
         Ok(result.into_iter().flatten().collect::<Vec<_>>())
     }
@@ -865,19 +862,68 @@ impl ApiClient {
             let cs = Some(self.cs());
             let url = uri.clone();
             set.spawn(async move {
-                client
-                    .post(url)
-                    .basic_auth(ck, cs)
-                    .json(&batch)
-                    .send()
-                    .await?
-                    .json::<BatchObject<T>>()
-                    .await
+                fetch::<BatchObject<T>>(client.post(url).basic_auth(ck, cs).json(&batch)).await
             });
         }
-        while let Some(Ok(Ok(v))) = set.join_next().await {
-            result.extend(v.delete)
+        while let Some(joined) = set.join_next().await {
+            result.extend(joined??.delete)
         }
         Ok(result.into_iter().flatten().collect::<Vec<_>>())
     }
+    /// Create entities with batch requests of 100, one request after another, keeping the order
+    /// of the objects. Every object gets its created entity, or the error of its batch item or of
+    /// its whole request, so a failing request doesn't hide what the other requests created.
+    pub(crate) async fn batch_create_each<T: Entity, O: Serialize + Clone>(
+        &self,
+        uri: Url,
+        create_objects: Vec<O>,
+    ) -> Vec<Result<T, String>> {
+        let mut result = Vec::with_capacity(create_objects.len());
+        for chunk in create_objects.chunks(100) {
+            let batch = BatchObject::builder().extend_create(chunk.to_vec()).build();
+            let request = self
+                .client
+                .post(uri.clone())
+                .basic_auth(self.ck(), Some(self.cs()))
+                .json(&batch);
+            let created = match fetch::<BatchObject<serde_json::Value>>(request).await {
+                Ok(response) => response.create.unwrap_or_default(),
+                Err(e) => {
+                    result.extend(chunk.iter().map(|_| Err(e.to_string())));
+                    continue;
+                }
+            };
+            let mut created = created.into_iter();
+            for _ in chunk {
+                // Failed items come back with ID 0 and an error instead of the entity.
+                let item = match created.next() {
+                    Some(value) => match value.get("error") {
+                        Some(error) => Err(error
+                            .get("message")
+                            .and_then(|m| m.as_str())
+                            .map_or_else(|| error.to_string(), String::from)),
+                        None => serde_json::from_value::<T>(value).map_err(|e| e.to_string()),
+                    },
+                    None => Err(String::from("Missing from the batch response")),
+                };
+                result.push(item);
+            }
+        }
+        result
+    }
+}
+/// Send a request and decode the response, see [`decode`].
+async fn fetch<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T> {
+    let response = request.send().await?;
+    let uri = response.url().to_owned();
+    let status = response.status();
+    let body = response.text().await?;
+    decode(&uri, status, &body)
+}
+/// Error responses and decoding errors are returned right away: retrying the request will not fix the response.
+pub(crate) fn decode<T: DeserializeOwned>(uri: &Url, status: StatusCode, body: &str) -> Result<T> {
+    if !status.is_success() {
+        return Err(WooError::from_response(status, body).into());
+    }
+    serde_json::from_str(body).map_err(|e| anyhow!("Failed to decode response from {uri}: {e}"))
 }
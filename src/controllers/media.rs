@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use reqwest::multipart::{Form, Part};
+
+use crate::{ApiClient, Media};
+
+use super::entities::decode;
+
+impl ApiClient {
+    /// This API uploads a file to the WordPress media library, e.g. a product image.
+    /// The returned attachment ID can be used as image of products and variations.
+    /// Requires the WordPress user and application password in the config.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, Product};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let bytes = std::fs::read("images/t-shirt.jpg")?;
+    ///     let media = client
+    ///         .upload_media(bytes, "t-shirt.jpg", "Blue t-shirt")
+    ///         .await?;
+    ///     info!("Uploaded {} as {}", media.source_url, media.id);
+    ///     let product = Product::builder()
+    ///         .name("T-shirt")
+    ///         .image_id(media.id)
+    ///         .build();
+    ///     let created: Product = client.create(product).await?;
+    ///     info!("Created product {}", created.id);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn upload_media(
+        &self,
+        bytes: impl Into<Vec<u8>>,
+        filename: &str,
+        alt: &str,
+    ) -> Result<Media> {
+        let uri = self.wp_url("wp/v2/media")?;
+        let mime = mime_guess::from_path(filename).first_or_octet_stream();
+        let part = Part::bytes(bytes.into())
+            .file_name(filename.to_owned())
+            .mime_str(mime.essence_str())?;
+        let form = Form::new()
+            .part("file", part)
+            .text("alt_text", alt.to_owned());
+        let (user, password) = self.wp_credentials();
+        tracing::debug!("Uploading {filename} to {uri}");
+        let response = self
+            .client
+            .post(uri.clone())
+            .basic_auth(user, password)
+            .multipart(form)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        decode(&uri, status, &body)
+    }
+    /// This API uploads a local file to the WordPress media library, see [`ApiClient::upload_media`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let media = client
+    ///         .upload_media_file("images/t-shirt.jpg", "Blue t-shirt")
+    ///         .await?;
+    ///     info!("Uploaded {} as {}", media.source_url, media.id);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn upload_media_file(&self, path: impl AsRef<Path>, alt: &str) -> Result<Media> {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("No file name in path {}", path.display()))?;
+        let bytes = tokio::fs::read(path).await?;
+        self.upload_media(bytes, filename, alt).await
+    }
+}
@@ -2,11 +2,12 @@ use serde::{Deserialize, Serialize};
 
 use anyhow::Result;
 
-use crate::Config;
+use crate::{Config, OrderWorkflow};
 pub mod coupons;
 pub mod customers;
 pub mod data;
 pub mod entities;
+pub mod media;
 pub mod order_notes;
 pub mod orders;
 pub mod payment_gateways;
@@ -18,6 +19,7 @@ pub mod product_shipping_classes;
 pub mod product_tags;
 pub mod product_variations;
 pub mod products;
+pub mod query;
 pub mod refunds;
 pub mod reports;
 pub mod settings;
@@ -25,6 +27,7 @@ pub mod shipping_methods;
 pub mod shipping_zone_locations;
 pub mod shipping_zone_methods;
 pub mod shipping_zones;
+pub mod system_status;
 pub mod tax_classes;
 pub mod tax_rates;
 pub mod webhooks;
@@ -38,8 +41,10 @@ pub trait Entity: Serialize + for<'de> Deserialize<'de> + Clone + Send + 'static
 pub struct ApiClient {
     ck: String,
     cs: String,
+    wp_auth: Option<(String, String)>,
     base_url: url::Url,
     client: reqwest::Client,
+    order_workflow: OrderWorkflow,
 }
 
 impl ApiClient {
@@ -55,6 +60,11 @@ impl ApiClient {
     pub fn new(config: &Config) -> Result<Self> {
         let ck = config.woo.ck.to_owned();
         let cs = config.woo.cs.to_owned();
+        let wp_auth = config
+            .woo
+            .wp_user
+            .to_owned()
+            .zip(config.woo.wp_password.to_owned());
         let client = reqwest::Client::builder().gzip(true).build()?;
         let base_url = match url::Url::parse(&config.woo.host) {
             Ok(url) => url.join("/wp-json/wc/v3/")?,
@@ -67,8 +77,10 @@ impl ApiClient {
         Ok(Self {
             ck,
             cs,
+            wp_auth,
             base_url,
             client,
+            order_workflow: OrderWorkflow::default(),
         })
     }
     /// Create a new ApiClient instance using environment variables
@@ -79,6 +91,9 @@ impl ApiClient {
     pub fn from_env() -> Result<Self> {
         let ck = std::env::var("WOO_CK")?;
         let cs = std::env::var("WOO_CS")?;
+        let wp_auth = std::env::var("WP_USER")
+            .ok()
+            .zip(std::env::var("WP_PASSWORD").ok());
         let base_url_raw = std::env::var("BASE_URL")?;
         let base_url = url::Url::parse(&format!("{base_url_raw}/wp-json/wc/v3/"))?;
         let client = reqwest::Client::builder().gzip(true).build()?;
@@ -86,8 +101,10 @@ impl ApiClient {
         Ok(Self {
             ck,
             cs,
+            wp_auth,
             base_url,
             client,
+            order_workflow: OrderWorkflow::default(),
         })
     }
     /// Get the Consumer Key
@@ -98,6 +115,19 @@ impl ApiClient {
     pub fn cs(&self) -> String {
         self.cs.clone()
     }
+    /// Credentials for WordPress endpoints: the application password if configured,
+    /// the consumer key and secret otherwise
+    fn wp_credentials(&self) -> (String, Option<String>) {
+        match &self.wp_auth {
+            Some((user, password)) => (user.to_owned(), Some(password.to_owned())),
+            None => (self.ck(), Some(self.cs())),
+        }
+    }
+    /// URL of a WordPress REST route, e.g. `wp/v2/media`, relative to the `wp-json` root
+    /// so that sites installed in a subdirectory keep their prefix
+    fn wp_url(&self, route: &str) -> Result<url::Url> {
+        Ok(self.base_url.join(&format!("../../{route}"))?)
+    }
     /// Get the reqwest Client
     pub fn client(&self) -> reqwest::Client {
         self.client.clone()
@@ -106,4 +136,13 @@ impl ApiClient {
     pub fn base_url(&self) -> String {
         self.base_url.to_string()
     }
+    /// Use other allowed order status transitions than the WooCommerce defaults
+    pub fn with_order_workflow(mut self, order_workflow: OrderWorkflow) -> Self {
+        self.order_workflow = order_workflow;
+        self
+    }
+    /// Get the allowed order status transitions
+    pub fn order_workflow(&self) -> &OrderWorkflow {
+        &self.order_workflow
+    }
 }
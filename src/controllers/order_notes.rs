@@ -25,7 +25,7 @@ impl<T> OrderNotesCreateBuilder<T> {
         OrderNotesCreateBuilder {
             note: WithNote(note.into()),
             customer_note: self.customer_note,
-            added_by_user: self.customer_note,
+            added_by_user: self.added_by_user,
         }
     }
     /// If true, the note will be shown to customers and they will be notified. If false, the note will be for admin reference only. Default is false.    
@@ -1,7 +1,13 @@
-use crate::{Billing, CurrencyISO, MetaData, OrderStatus, Shipping, TaxStatus};
+use crate::{
+    controllers::Entity, models::cart::CartItem, ApiClient, Billing, BulkOrderTransition, Cart,
+    Coupon, CurrencyISO, ListQuery, MetaBag, MetaData, Order, OrderEmailTemplate, OrderNotes,
+    OrderStatus, OrderTransition, OrderUpdateFailure, Product, ProductType, ProductVariation,
+    Shipping, TaxStatus,
+};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use tokio::task::JoinSet;
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateOrder {
@@ -99,6 +105,11 @@ impl OrderLineItemCreate {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
     /// Product price.
     pub fn price(mut self, price: f64) -> Self {
         let _ = self.price.insert(price);
@@ -140,6 +151,11 @@ impl ShippingLineCreate {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
 }
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -151,6 +167,16 @@ pub struct OrderFeeLineCreate {
     meta_data: Option<Vec<MetaData>>,
 }
 impl OrderFeeLineCreate {
+    /// Taxable fee in the standard tax class.
+    pub fn new(name: impl Into<String>, total: impl Into<String>) -> Self {
+        OrderFeeLineCreate {
+            name: name.into(),
+            tax_class: String::new(),
+            tax_status: TaxStatus::Taxable,
+            total: total.into(),
+            meta_data: None,
+        }
+    }
     /// Fee name.
     pub fn name(mut self, name: impl Into<String>) -> Self {
         self.name = name.into();
@@ -181,6 +207,11 @@ impl OrderFeeLineCreate {
         self.meta_data.get_or_insert(vec![]).push(meta_data);
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
 }
 
 #[skip_serializing_none]
@@ -363,6 +394,21 @@ impl CreateOrderBuilder {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
+    /// Billing address, replacing the billing fields set before.
+    pub fn billing(mut self, billing: Billing) -> Self {
+        let _ = self.billing.insert(billing);
+        self
+    }
+    /// Shipping address, replacing the shipping fields set before.
+    pub fn shipping(mut self, shipping: Shipping) -> Self {
+        let _ = self.shipping.insert(shipping);
+        self
+    }
     /// Line items data.
     pub fn line_item(mut self, line_item: OrderLineItemCreate) -> Self {
         self.line_items.get_or_insert(vec![]).push(line_item);
@@ -598,6 +644,11 @@ impl UpdateOrderBuilder {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(mut self, meta_bag: &MetaBag) -> Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
     /// Line items data.
     pub fn line_item(mut self, line_item: OrderLineItemCreate) -> Self {
         self.line_items.get_or_insert(vec![]).push(line_item);
@@ -648,3 +699,374 @@ impl UpdateOrderBuilder {
         }
     }
 }
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SendOrderEmail {
+    /// Email address to send to. Saved as billing email if the order has none.
+    email: Option<String>,
+    /// Replace the existing billing email with the given one.
+    force_email_update: Option<bool>,
+    /// Email template ID, see `ApiClient::order_email_templates`.
+    template_id: Option<String>,
+}
+impl SendOrderEmail {
+    /// Email address to send to. Saved as billing email if the order has none.
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        let _ = self.email.insert(email.into());
+        self
+    }
+    /// Replace the existing billing email with the given one.
+    pub fn force_email_update(mut self) -> Self {
+        let _ = self.force_email_update.insert(true);
+        self
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OrderActionResponse {
+    message: String,
+}
+impl ApiClient {
+    /// This API lists the email templates that can be sent for an order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let templates = client.order_email_templates(12345).await?;
+    ///     for template in templates {
+    ///         info!("{}: {}", template.id, template.title);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn order_email_templates(&self, order_id: i32) -> Result<Vec<OrderEmailTemplate>> {
+        let uri = self.base_url.join(&format!(
+            "{}{order_id}/actions/email_templates",
+            Order::endpoint()
+        ))?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API sends the order details to the customer.
+    /// Errors are [`crate::WooError`], e.g. with `WoocommerceRestMissingEmail` code.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, Order, WooError, WooErrorCode};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     match client.send_order_details(12345, Order::email()).await {
+    ///         Ok(message) => info!("{message}"),
+    ///         Err(e) => match e.downcast_ref::<WooError>() {
+    ///             Some(e) if e.code == WooErrorCode::WoocommerceRestMissingEmail => {
+    ///                 let email = Order::email().email("customer@example.com");
+    ///                 let message = client.send_order_details(12345, email).await?;
+    ///                 info!("{message}");
+    ///             }
+    ///             _ => return Err(e),
+    ///         },
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send_order_details(&self, order_id: i32, email: SendOrderEmail) -> Result<String> {
+        self.order_action(order_id, "send_order_details", &email)
+            .await
+    }
+    /// This API sends an email of the given template for an order, e.g. `customer_completed_order`.
+    /// Errors are [`crate::WooError`], e.g. with `WoocommerceRestInvalidEmailTemplate` code.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, Order};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let message = client
+    ///         .send_email(12345, "customer_completed_order", Order::email())
+    ///         .await?;
+    ///     info!("{message}");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send_email(
+        &self,
+        order_id: i32,
+        template_id: impl Into<String>,
+        mut email: SendOrderEmail,
+    ) -> Result<String> {
+        let _ = email.template_id.insert(template_id.into());
+        self.order_action(order_id, "send_email", &email).await
+    }
+    async fn order_action(
+        &self,
+        order_id: i32,
+        action: &str,
+        email: &SendOrderEmail,
+    ) -> Result<String> {
+        let uri = self
+            .base_url
+            .join(&format!("{}{order_id}/actions/{action}", Order::endpoint()))?;
+        let response: OrderActionResponse = self.post_request_with_tries(email, uri, 3).await?;
+        Ok(response.message)
+    }
+    /// This API moves an order to another status and adds a note, if the workflow of the client
+    /// allows the move, see [`ApiClient::with_order_workflow`].
+    /// Moves that aren't allowed fail with [`crate::OrderTransitionError`].
+    /// Without a note text the note tells the old and new status. A note that can't be added
+    /// doesn't fail the call, as the order has already moved; see `note_error`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, OrderStatus, OrderTransitionError};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     match client
+    ///         .transition_order(12345, OrderStatus::Completed, "Delivered")
+    ///         .await
+    ///     {
+    ///         Ok(transition) => info!("Order was {}", transition.from),
+    ///         Err(e) => match e.downcast_ref::<OrderTransitionError>() {
+    ///             Some(error) => info!("{error}"),
+    ///             None => return Err(e),
+    ///         },
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn transition_order(
+        &self,
+        order_id: i32,
+        to: OrderStatus,
+        note: impl Into<String>,
+    ) -> Result<OrderTransition> {
+        let order = self.retrieve::<Order>(order_id).await?;
+        self.order_workflow().check(&order, &to)?;
+        let update = Order::update().status(to.to_owned()).build();
+        let updated = self.update::<Order>(order_id, update).await?;
+        let note = self
+            .add_transition_note(order_id, &order.status, &to, &note.into())
+            .await;
+        Ok(OrderTransition::new(order.status, updated, note))
+    }
+    /// This API moves orders to another status and adds a note to each of them.
+    /// Orders the workflow of the client doesn't allow to move are left unchanged and reported,
+    /// as are orders the update failed for. Notes that can't be added are reported by order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, OrderStatus};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let result = client
+    ///         .transition_orders(&[12345, 12346, 12347], OrderStatus::Completed, "Shipped")
+    ///         .await?;
+    ///     info!("{} orders completed", result.transitioned.len());
+    ///     for rejected in result.rejected {
+    ///         info!("{rejected}");
+    ///     }
+    ///     for failed in result.failed {
+    ///         info!("{failed}");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn transition_orders(
+        &self,
+        order_ids: &[i32],
+        to: OrderStatus,
+        note: impl Into<String>,
+    ) -> Result<BulkOrderTransition> {
+        let note = note.into();
+        let mut result = BulkOrderTransition::default();
+        if order_ids.is_empty() {
+            return Ok(result);
+        }
+        let include = order_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let orders = self
+            .list_all_with_query::<Order>(&ListQuery::new().param("include", include))
+            .await?;
+        let missing = order_ids
+            .iter()
+            .filter(|id| !orders.iter().any(|o| o.id == **id))
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(anyhow!("Orders not found: {}", missing.join(", ")));
+        }
+        let mut allowed = vec![];
+        for order in orders {
+            match self.order_workflow().check(&order, &to) {
+                Ok(()) => allowed.push(order),
+                Err(error) => result.rejected.push(error),
+            }
+        }
+        let updates = allowed
+            .iter()
+            .map(|order| Order::update().id(order.id).status(to.to_owned()).build())
+            .collect::<Vec<_>>();
+        let mut updated = self.batch_update::<Order, _>(updates).await?;
+        let mut set = JoinSet::new();
+        for original in allowed {
+            // Failed items come back with the order ID and an error instead of the order.
+            let order = updated
+                .iter()
+                .position(|o| o.id == original.id)
+                .map(|i| updated.swap_remove(i));
+            let order = match order {
+                Some(order) if order.status == to => order,
+                order => {
+                    let error = order
+                        .as_ref()
+                        .and_then(|o| o.extra.get("error")?.get("message")?.as_str())
+                        .unwrap_or("order not updated");
+                    result.failed.push(OrderUpdateFailure {
+                        order_id: original.id,
+                        from: original.status,
+                        error: error.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let client = self.clone();
+            let from = original.status;
+            let to = to.to_owned();
+            let note = note.to_owned();
+            set.spawn(async move {
+                let note = client
+                    .add_transition_note(order.id, &from, &to, &note)
+                    .await;
+                OrderTransition::new(from, order, note)
+            });
+        }
+        while let Some(joined) = set.join_next().await {
+            result.transitioned.push(joined?);
+        }
+        result.transitioned.sort_by_key(|t| t.order.id);
+        result.failed.sort_by_key(|f| f.order_id);
+        Ok(result)
+    }
+    async fn add_transition_note(
+        &self,
+        order_id: i32,
+        from: &OrderStatus,
+        to: &OrderStatus,
+        note: &str,
+    ) -> Result<OrderNotes> {
+        let text = match note.trim().is_empty() {
+            true => format!("Order status changed from {from} to {to}."),
+            false => note.to_string(),
+        };
+        let note = OrderNotes::create().note(text).build();
+        self.create_subentity::<OrderNotes>(order_id, note).await
+    }
+    /// This API loads the products and variations added to a cart by ID or SKU,
+    /// so their stock can be checked and the order created, and the coupons added by code,
+    /// so free shipping requirements can be checked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Cart, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let cart = Cart::new().product_id(3982, 2).sku("MUG-BLUE", 1);
+    ///     let cart = client.resolve_cart(cart).await?;
+    ///     for problem in cart.problems() {
+    ///         info!("{problem}");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn resolve_cart(&self, mut cart: Cart) -> Result<Cart> {
+        for item in cart.items.iter_mut() {
+            let (product, quantity) = match item {
+                CartItem::Loaded { .. } => continue,
+                CartItem::Id { id, quantity } => (self.retrieve::<Product>(*id).await?, *quantity),
+                CartItem::Sku { sku, quantity } => {
+                    let query = ListQuery::new().param("sku", sku.as_str());
+                    let product = self
+                        .list_all_with_query::<Product>(&query)
+                        .await?
+                        .into_iter()
+                        .find(|p| p.sku == *sku)
+                        .ok_or_else(|| anyhow!("No product with SKU {sku}"))?;
+                    (product, *quantity)
+                }
+            };
+            *item = match &product.product_type {
+                ProductType::Other(product_type) if product_type == "variation" => {
+                    let (parent, variation) = tokio::join!(
+                        self.retrieve::<Product>(product.parent_id),
+                        self.retrieve_subentity::<ProductVariation>(product.parent_id, product.id)
+                    );
+                    CartItem::Loaded {
+                        product: Box::new(parent?),
+                        variation: Some(Box::new(variation?)),
+                        quantity,
+                    }
+                }
+                _ => CartItem::Loaded {
+                    product: Box::new(product),
+                    variation: None,
+                    quantity,
+                },
+            };
+        }
+        for code in cart.coupons_to_load() {
+            let query = ListQuery::new().param("code", &code);
+            let coupon = self
+                .list_all_with_query::<Coupon>(&query)
+                .await?
+                .into_iter()
+                .find(|c| c.code.eq_ignore_ascii_case(&code))
+                .ok_or_else(|| anyhow!("No coupon with code {code}"))?;
+            cart.loaded_coupons.push(coupon);
+        }
+        Ok(cart)
+    }
+}
@@ -1,7 +1,138 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{controllers::Entity, ApiClient, PaymentGateway};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PaymentGatewayUpdate {
+    /// Payment gateway title on checkout.
+    title: Option<String>,
+    /// Payment gateway description on checkout.
+    description: Option<String>,
+    /// Payment gateway sort order.
+    order: Option<i32>,
     /// Payment gateway enabled status.
-    pub enabled: bool,
+    enabled: Option<bool>,
+    /// Payment gateway settings values, by setting id.
+    settings: Option<BTreeMap<String, serde_json::Value>>,
+}
+impl PaymentGatewayUpdate {
+    /// Payment gateway title on checkout.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        let _ = self.title.insert(title.into());
+        self
+    }
+    /// Payment gateway description on checkout.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        let _ = self.description.insert(description.into());
+        self
+    }
+    /// Payment gateway sort order.
+    pub fn order(mut self, order: i32) -> Self {
+        let _ = self.order.insert(order);
+        self
+    }
+    /// Enable payment gateway.
+    pub fn enabled(mut self) -> Self {
+        let _ = self.enabled.insert(true);
+        self
+    }
+    /// Disable payment gateway.
+    pub fn disabled(mut self) -> Self {
+        let _ = self.enabled.insert(false);
+        self
+    }
+    /// Value of any setting by its id, e.g. `instructions` or `enable_for_virtual`.
+    /// Checkbox settings take `"yes"` or `"no"`.
+    pub fn setting(mut self, id: impl Into<String>, value: impl Serialize) -> Self {
+        self.settings
+            .get_or_insert(BTreeMap::new())
+            .insert(id.into(), serde_json::json!(value));
+        self
+    }
+    /// Checkbox setting value by its id.
+    pub fn checkbox(self, id: impl Into<String>, checked: bool) -> Self {
+        self.setting(id, if checked { "yes" } else { "no" })
+    }
+    /// Instructions added to the thank you page and emails.
+    pub fn instructions(self, instructions: impl Into<String>) -> Self {
+        self.setting("instructions", instructions.into())
+    }
+    /// Shipping methods the gateway is available for, e.g. `flat_rate` or `local_pickup`.
+    /// Used by cash on delivery.
+    pub fn enable_for_methods<T: Into<String>>(self, methods: impl IntoIterator<Item = T>) -> Self {
+        let methods = methods.into_iter().map(Into::into).collect::<Vec<String>>();
+        self.setting("enable_for_methods", methods)
+    }
+    /// Accept the gateway for virtual orders. Used by cash on delivery.
+    pub fn enable_for_virtual(self, enable: bool) -> Self {
+        self.checkbox("enable_for_virtual", enable)
+    }
+}
+impl ApiClient {
+    /// This API lets you retrieve a payment gateway by id, e.g. `bacs` or `cod`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let gateway = client.retrieve_payment_gateway("cod").await?;
+    ///     info!("{} enabled: {}", gateway.title, gateway.enabled);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn retrieve_payment_gateway(&self, gateway_id: &str) -> Result<PaymentGateway> {
+        let uri = self
+            .base_url
+            .join(&PaymentGateway::endpoint())?
+            .join(gateway_id)?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lets you make changes to a payment gateway.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, PaymentGateway};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let update = PaymentGateway::update()
+    ///         .enabled()
+    ///         .title("Cash on delivery")
+    ///         .enable_for_methods(["flat_rate", "local_pickup"])
+    ///         .enable_for_virtual(false);
+    ///     let gateway = client.update_payment_gateway("cod", update).await?;
+    ///     info!("{} enabled: {}", gateway.title, gateway.enabled);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update_payment_gateway(
+        &self,
+        gateway_id: &str,
+        update: PaymentGatewayUpdate,
+    ) -> Result<PaymentGateway> {
+        let uri = self
+            .base_url
+            .join(&PaymentGateway::endpoint())?
+            .join(gateway_id)?;
+        self.put_request_with_tries(&update, uri, 3).await
+    }
 }
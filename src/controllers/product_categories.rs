@@ -46,7 +46,8 @@ impl CategoryCreate {
     /// Image data.
     pub fn image(mut self, img_src: impl Into<String>) -> Self {
         let _ = self.image.insert(ImageDTO {
-            src: img_src.into(),
+            id: None,
+            src: Some(img_src.into()),
         });
         self
     }
@@ -102,7 +103,8 @@ impl CategoryUpdate {
     /// Image data.
     pub fn image(mut self, img_src: impl Into<String>) -> Self {
         let _ = self.image.insert(ImageDTO {
-            src: img_src.into(),
+            id: None,
+            src: Some(img_src.into()),
         });
         self
     }
@@ -1,8 +1,13 @@
-use chrono::NaiveDateTime;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use crate::{BackordersStatus, Dimensions, MetaData, ProductStatus, StockStatus, TaxStatus};
+use crate::{
+    controllers::Entity, ApiClient, BackordersStatus, Dimensions, FailedVariation,
+    GeneratedVariations, MetaBag, MetaData, Product, ProductAttribute, ProductStatus,
+    ProductVariation, StockStatus, TaxStatus,
+};
 
 use super::products::{DefaultAttributeDTO, DownloadDTO, ImageDTO};
 
@@ -14,8 +19,8 @@ pub struct ProductVariationModify {
     sku: Option<String>,
     regular_price: Option<String>,
     sale_price: Option<String>,
-    date_on_sale_from: Option<NaiveDateTime>,
-    date_on_sale_to: Option<NaiveDateTime>,
+    date_on_sale_from_gmt: Option<NaiveDateTime>,
+    date_on_sale_to_gmt: Option<NaiveDateTime>,
     status: Option<ProductStatus>,
     #[serde(rename = "virtual")]
     is_virtual: Option<bool>,
@@ -44,8 +49,8 @@ pub struct ProductVariationModifyBuilder {
     sku: Option<String>,
     regular_price: Option<String>,
     sale_price: Option<String>,
-    date_on_sale_from: Option<NaiveDateTime>,
-    date_on_sale_to: Option<NaiveDateTime>,
+    date_on_sale_from_gmt: Option<NaiveDateTime>,
+    date_on_sale_to_gmt: Option<NaiveDateTime>,
     status: Option<ProductStatus>,
     is_virtual: Option<bool>,
     downloadable: Option<bool>,
@@ -92,22 +97,14 @@ impl ProductVariationModifyBuilder {
         let _ = self.sale_price.insert(sale_price.into());
         self
     }
-    /// Start date of sale price, in the site's timezone.
-    pub fn date_on_sale_from(&mut self, year: i32, month: u32, day: u32) -> &mut Self {
-        let dt = chrono::NaiveDate::from_ymd_opt(year, month, day)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap();
-        let _ = self.date_on_sale_from.insert(dt);
+    /// Start date of sale price, sent as GMT.
+    pub fn date_on_sale_from(&mut self, date: DateTime<Utc>) -> &mut Self {
+        let _ = self.date_on_sale_from_gmt.insert(date.naive_utc());
         self
     }
-    /// End date of sale price, in the site's timezone.
-    pub fn date_on_sale_to(&mut self, year: i32, month: u32, day: u32) -> &mut Self {
-        let dt = chrono::NaiveDate::from_ymd_opt(year, month, day)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap();
-        let _ = self.date_on_sale_to.insert(dt);
+    /// End date of sale price, sent as GMT.
+    pub fn date_on_sale_to(&mut self, date: DateTime<Utc>) -> &mut Self {
+        let _ = self.date_on_sale_to_gmt.insert(date.naive_utc());
         self
     }
     /// Variation status. Options: draft, pending, private and publish. Default is publish.
@@ -189,6 +186,7 @@ impl ProductVariationModifyBuilder {
             length: length.into(),
             width: width.into(),
             height: height.into(),
+            ..Default::default()
         };
         let _ = self.dimensions.insert(d);
         self
@@ -201,7 +199,16 @@ impl ProductVariationModifyBuilder {
     /// Variation image data.
     pub fn image(&mut self, img_src: impl Into<String>) -> &mut Self {
         let _ = self.image.insert(ImageDTO {
-            src: img_src.into(),
+            id: None,
+            src: Some(img_src.into()),
+        });
+        self
+    }
+    /// Variation image, by attachment ID of an uploaded image.
+    pub fn image_id(&mut self, attachment_id: i32) -> &mut Self {
+        let _ = self.image.insert(ImageDTO {
+            id: Some(attachment_id),
+            src: None,
         });
         self
     }
@@ -235,6 +242,11 @@ impl ProductVariationModifyBuilder {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(&mut self, meta_bag: &MetaBag) -> &mut Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
     pub fn build(&self) -> ProductVariationModify {
         ProductVariationModify {
             id: self.id,
@@ -242,8 +254,8 @@ impl ProductVariationModifyBuilder {
             sku: self.sku.to_owned(),
             regular_price: self.regular_price.to_owned(),
             sale_price: self.sale_price.to_owned(),
-            date_on_sale_from: self.date_on_sale_from,
-            date_on_sale_to: self.date_on_sale_to,
+            date_on_sale_from_gmt: self.date_on_sale_from_gmt,
+            date_on_sale_to_gmt: self.date_on_sale_to_gmt,
             status: self.status.to_owned(),
             is_virtual: self.is_virtual,
             downloadable: self.downloadable,
@@ -266,3 +278,416 @@ impl ProductVariationModifyBuilder {
         }
     }
 }
+/// Attribute with the options to generate variations from.
+#[derive(Debug, Clone, Default)]
+pub struct VariationAttribute {
+    /// Attribute ID, None for custom product attributes.
+    pub id: Option<i32>,
+    /// Attribute name.
+    pub name: String,
+    /// List of term names of the attribute.
+    pub options: Vec<String>,
+}
+impl From<&ProductAttribute> for VariationAttribute {
+    fn from(value: &ProductAttribute) -> Self {
+        VariationAttribute {
+            id: (value.id != 0).then_some(value.id),
+            name: value.name.to_owned(),
+            options: value.options.to_owned(),
+        }
+    }
+}
+/// Prices, SKU and stock applied to every generated variation.
+#[derive(Debug, Clone, Default)]
+pub struct VariationTemplate {
+    regular_price: Option<f64>,
+    sale_price: Option<f64>,
+    surcharges: Vec<(String, String, f64)>,
+    sku: Option<String>,
+    stock_quantity: Option<i32>,
+    status: Option<ProductStatus>,
+}
+impl VariationTemplate {
+    pub fn new() -> Self {
+        VariationTemplate::default()
+    }
+    /// Variation regular price.
+    pub fn regular_price(mut self, price: f64) -> Self {
+        let _ = self.regular_price.insert(price);
+        self
+    }
+    /// Variation sale price.
+    pub fn sale_price(mut self, price: f64) -> Self {
+        let _ = self.sale_price.insert(price);
+        self
+    }
+    /// Amount added to the prices of variations with the option, e.g. `2.0` for size `XL`.
+    pub fn surcharge(
+        mut self,
+        attribute: impl Into<String>,
+        option: impl Into<String>,
+        amount: f64,
+    ) -> Self {
+        self.surcharges
+            .push((attribute.into(), option.into(), amount));
+        self
+    }
+    /// SKU pattern. `{options}` is replaced with the option slugs joined by `-`,
+    /// `{<attribute name>}` with the slug of the option of that attribute,
+    /// e.g. `TSHIRT-{Color}-{Size}` gives `TSHIRT-blue-xl`.
+    pub fn sku(mut self, pattern: impl Into<String>) -> Self {
+        let _ = self.sku.insert(pattern.into());
+        self
+    }
+    /// Manage stock of every variation, starting with the quantity.
+    pub fn stock_quantity(mut self, stock_quantity: i32) -> Self {
+        let _ = self.stock_quantity.insert(stock_quantity);
+        self
+    }
+    /// Variation status.
+    pub fn status(mut self, status: ProductStatus) -> Self {
+        let _ = self.status.insert(status);
+        self
+    }
+    /// Variations for every combination of the attribute options,
+    /// except the ones matching an existing variation.
+    pub fn generate(
+        &self,
+        attributes: &[VariationAttribute],
+        existing: &[ProductVariation],
+    ) -> Vec<ProductVariationModify> {
+        self.generate_with_options(attributes, existing)
+            .into_iter()
+            .map(|(_, variation)| variation)
+            .collect()
+    }
+    /// Generated variations with their options, as `(attribute name, option)` pairs.
+    fn generate_with_options(
+        &self,
+        attributes: &[VariationAttribute],
+        existing: &[ProductVariation],
+    ) -> Vec<(Vec<(String, String)>, ProductVariationModify)> {
+        combinations(attributes)
+            .into_iter()
+            .filter(|combination| !existing.iter().any(|v| same_options(v, combination)))
+            .map(|combination| {
+                let options = combination
+                    .iter()
+                    .map(|(attribute, option)| (attribute.name.to_owned(), option.to_string()))
+                    .collect();
+                (options, self.variation(&combination))
+            })
+            .collect()
+    }
+    fn variation(&self, combination: &[(&VariationAttribute, &str)]) -> ProductVariationModify {
+        let mut builder = ProductVariationModifyBuilder::default();
+        for (attribute, option) in combination {
+            builder.attribute(attribute.id, attribute.name.to_owned(), *option);
+        }
+        let surcharge: f64 = self
+            .surcharges
+            .iter()
+            .filter(|(name, option, _)| {
+                combination
+                    .iter()
+                    .any(|(a, o)| same_attribute_name(&a.name, name) && same_option(o, option))
+            })
+            .map(|(_, _, amount)| amount)
+            .sum();
+        if let Some(price) = self.regular_price {
+            builder.regular_price(format_price(price + surcharge));
+        }
+        if let Some(price) = self.sale_price {
+            builder.sale_price(format_price(price + surcharge));
+        }
+        if let Some(pattern) = &self.sku {
+            let mut sku = pattern.replace(
+                "{options}",
+                &combination
+                    .iter()
+                    .map(|(_, option)| slug(option))
+                    .collect::<Vec<_>>()
+                    .join("-"),
+            );
+            for (attribute, option) in combination {
+                sku = sku.replace(&format!("{{{}}}", attribute.name), &slug(option));
+            }
+            builder.sku(sku);
+        }
+        if let Some(stock_quantity) = self.stock_quantity {
+            builder.manage_stock().stock_quantity(stock_quantity);
+        }
+        if let Some(status) = &self.status {
+            builder.status(status.to_owned());
+        }
+        builder.build()
+    }
+}
+fn combinations(attributes: &[VariationAttribute]) -> Vec<Vec<(&VariationAttribute, &str)>> {
+    if attributes.is_empty() {
+        return vec![];
+    }
+    attributes
+        .iter()
+        .fold(vec![vec![]], |combinations, attribute| {
+            combinations
+                .iter()
+                .flat_map(|combination| {
+                    attribute.options.iter().map(move |option| {
+                        let mut combination = combination.clone();
+                        combination.push((attribute, option.as_str()));
+                        combination
+                    })
+                })
+                .collect()
+        })
+}
+/// Attributes are matched by id, or by name for custom attributes.
+fn same_options(variation: &ProductVariation, combination: &[(&VariationAttribute, &str)]) -> bool {
+    variation.attributes.len() == combination.len()
+        && combination.iter().all(|(attribute, option)| {
+            variation.attributes.iter().any(|a| {
+                let same_attribute = match attribute.id {
+                    Some(id) if a.id != 0 => a.id == id,
+                    _ => same_attribute_name(&a.name, &attribute.name),
+                };
+                same_attribute && same_option(&a.option, option)
+            })
+        })
+}
+/// Attribute names are compared by slug, so `Color`, `pa_color` and `attribute_pa_color` are the same.
+pub(crate) fn same_attribute_name(a: &str, b: &str) -> bool {
+    let name = |value: &str| {
+        let value = value.to_lowercase();
+        let value = value.strip_prefix("attribute_").unwrap_or(&value);
+        slug(value.strip_prefix("pa_").unwrap_or(value))
+    };
+    name(a) == name(b)
+}
+/// Options are compared by name or slug, e.g. `Dark Red` and `dark-red`.
+pub(crate) fn same_option(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b) || slug(a) == slug(b)
+}
+fn format_price(price: f64) -> String {
+    ((price * 100.0).round() / 100.0).to_string()
+}
+fn slug(value: &str) -> String {
+    value
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+impl ApiClient {
+    /// Create the missing variations of a variable product,
+    /// one for every combination of the options of its variation attributes.
+    /// Returns the created variations and the ones the batch failed to create,
+    /// e.g. for a duplicate SKU.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, Product, ProductVariation};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let product = client.retrieve::<Product>(3982).await?;
+    ///     let template = ProductVariation::template()
+    ///         .regular_price(20.0)
+    ///         .surcharge("Size", "XL", 2.0)
+    ///         .sku(format!("{}-{{options}}", product.sku));
+    ///     let generated = client
+    ///         .generate_variations(product.id, &product.variation_attributes(), &template)
+    ///         .await?;
+    ///     info!("Created {} variations", generated.created.len());
+    ///     for failed in &generated.failed {
+    ///         info!("Failed to create {:?}: {}", failed.options, failed.error);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn generate_variations(
+        &self,
+        product_id: i32,
+        attributes: &[VariationAttribute],
+        template: &VariationTemplate,
+    ) -> Result<GeneratedVariations> {
+        let existing = self
+            .list_all_subentities_paged::<ProductVariation>(product_id, &[])
+            .await?;
+        let (options, variations): (Vec<_>, Vec<_>) = template
+            .generate_with_options(attributes, &existing)
+            .into_iter()
+            .unzip();
+        let mut generated = GeneratedVariations::default();
+        if variations.is_empty() {
+            return Ok(generated);
+        }
+        let uri = self
+            .base_url
+            .join(&ProductVariation::child_endpoint(product_id))?
+            .join("batch")?;
+        let results = self
+            .batch_create_each::<ProductVariation, _>(uri, variations)
+            .await;
+        for (options, result) in options.into_iter().zip(results) {
+            match result {
+                Ok(variation) => generated.created.push(variation),
+                Err(error) => generated.failed.push(FailedVariation { options, error }),
+            }
+        }
+        Ok(generated)
+    }
+}
+impl ApiClient {
+    /// Find the variation of a product matching the selected attributes, the way the store does on the product page.
+    /// Attributes missing from the selection take the product default value.
+    /// Returns None if no variation matches.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let variation = client
+    ///         .find_variation(3982, &[("Color", "Red"), ("Size", "XL")])
+    ///         .await?;
+    ///     if let Some(variation) = variation {
+    ///         info!("Variation {} costs {}", variation.id, variation.price);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn find_variation(
+        &self,
+        product_id: i32,
+        attributes: &[(&str, &str)],
+    ) -> Result<Option<ProductVariation>> {
+        let (product, variations) = tokio::join!(
+            self.retrieve::<Product>(product_id),
+            self.list_all_subentities_paged::<ProductVariation>(product_id, &[])
+        );
+        let variations = variations?;
+        Ok(product?.match_variation(&variations, attributes).cloned())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes() -> Vec<VariationAttribute> {
+        vec![
+            VariationAttribute {
+                id: Some(1),
+                name: String::from("Color"),
+                options: vec![String::from("Dark Red"), String::from("Blue")],
+            },
+            VariationAttribute {
+                id: None,
+                name: String::from("Size"),
+                options: vec![String::from("S"), String::from("XL")],
+            },
+        ]
+    }
+    fn options(variation: &ProductVariationModify) -> Vec<(Option<i32>, String, String)> {
+        variation
+            .attributes
+            .iter()
+            .flatten()
+            .map(|a| (a.id, a.name.to_owned(), a.option.to_owned()))
+            .collect()
+    }
+    fn existing(attributes: serde_json::Value) -> ProductVariation {
+        serde_json::from_value(serde_json::json!({ "id": 7, "attributes": attributes })).unwrap()
+    }
+    #[test]
+    fn every_combination_of_options() {
+        let variations = VariationTemplate::new().generate(&attributes(), &[]);
+        let combinations = variations
+            .iter()
+            .map(|v| {
+                options(v)
+                    .into_iter()
+                    .map(|(_, _, option)| option)
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            combinations,
+            ["Dark Red/S", "Dark Red/XL", "Blue/S", "Blue/XL"]
+        );
+        assert_eq!(
+            options(&variations[0]),
+            [
+                (Some(1), String::from("Color"), String::from("Dark Red")),
+                (None, String::from("Size"), String::from("S"))
+            ]
+        );
+        assert!(VariationTemplate::new().generate(&[], &[]).is_empty());
+    }
+    #[test]
+    fn skips_existing_option_sets() {
+        let complete = existing(serde_json::json!([
+            {"id": 1, "name": "Color", "option": "dark-red"},
+            {"id": 0, "name": "size", "option": "xl"}
+        ]));
+        let partial = existing(serde_json::json!([
+            {"id": 1, "name": "Color", "option": "Blue"}
+        ]));
+        let variations = VariationTemplate::new().generate(&attributes(), &[complete, partial]);
+        assert_eq!(variations.len(), 3);
+        assert!(variations
+            .iter()
+            .all(|v| options(v)[0].2 != "Dark Red" || options(v)[1].2 != "XL"));
+    }
+    #[test]
+    fn sku_patterns() {
+        let variations = VariationTemplate::new()
+            .sku("TS-{options}")
+            .generate(&attributes(), &[]);
+        assert_eq!(variations[1].sku.as_deref(), Some("TS-dark-red-xl"));
+        let variations = VariationTemplate::new()
+            .sku("TS-{Size}-{Color}")
+            .generate(&attributes(), &[]);
+        assert_eq!(variations[2].sku.as_deref(), Some("TS-s-blue"));
+    }
+    #[test]
+    fn prices_with_surcharges() {
+        let variations = VariationTemplate::new()
+            .regular_price(20.0)
+            .sale_price(15.0)
+            .surcharge("pa_size", "xl", 2.5)
+            .surcharge("Color", "blue", 0.1)
+            .stock_quantity(4)
+            .generate(&attributes(), &[]);
+        let prices = variations
+            .iter()
+            .map(|v| (v.regular_price.as_deref(), v.sale_price.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prices,
+            [
+                (Some("20"), Some("15")),
+                (Some("22.5"), Some("17.5")),
+                (Some("20.1"), Some("15.1")),
+                (Some("22.6"), Some("17.6"))
+            ]
+        );
+        assert_eq!(variations[0].manage_stock, Some(true));
+        assert_eq!(variations[0].stock_quantity, Some(4));
+    }
+}
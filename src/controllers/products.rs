@@ -1,11 +1,13 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
 use crate::{
-    BackordersStatus, CatalogVisibility, MetaData, ProductStatus, ProductType, StockStatus,
-    TaxStatus,
+    BackordersStatus, CatalogVisibility, MetaBag, MetaData, ProductStatus, ProductType,
+    StockStatus, TaxStatus,
 };
+
+use super::product_variations::VariationAttribute;
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductModify {
@@ -23,8 +25,8 @@ pub struct ProductModify {
     sku: Option<String>,
     regular_price: Option<String>,
     sale_price: Option<String>,
-    date_on_sale_from: Option<NaiveDateTime>,
-    date_on_sale_to: Option<NaiveDateTime>,
+    date_on_sale_from_gmt: Option<NaiveDateTime>,
+    date_on_sale_to_gmt: Option<NaiveDateTime>,
     #[serde(rename = "virtual")]
     is_virtual: Option<bool>,
     downloadable: Option<bool>,
@@ -62,6 +64,19 @@ impl ProductModify {
     pub fn builder() -> ProductModifyBuilder {
         ProductModifyBuilder::default()
     }
+    /// Attributes used for variations, to generate them once the product is created.
+    pub fn variation_attributes(&self) -> Vec<VariationAttribute> {
+        self.attributes
+            .iter()
+            .flatten()
+            .filter(|a| a.variation)
+            .map(|a| VariationAttribute {
+                id: a.id,
+                name: a.name.to_owned(),
+                options: a.options.to_owned(),
+            })
+            .collect()
+    }
 }
 #[derive(Default)]
 pub struct ProductModifyBuilder {
@@ -78,8 +93,8 @@ pub struct ProductModifyBuilder {
     sku: Option<String>,
     regular_price: Option<String>,
     sale_price: Option<String>,
-    date_on_sale_from: Option<NaiveDateTime>,
-    date_on_sale_to: Option<NaiveDateTime>,
+    date_on_sale_from_gmt: Option<NaiveDateTime>,
+    date_on_sale_to_gmt: Option<NaiveDateTime>,
     is_virtual: Option<bool>,
     downloadable: Option<bool>,
     downloads: Option<Vec<DownloadDTO>>,
@@ -182,22 +197,14 @@ impl ProductModifyBuilder {
         let _ = self.sale_price.insert(sale_price.into());
         self
     }
-    /// Start date of sale price, in the site's timezone.
-    pub fn date_on_sale_from(&mut self, year: i32, month: u32, day: u32) -> &mut Self {
-        let dt = chrono::NaiveDate::from_ymd_opt(year, month, day)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap();
-        let _ = self.date_on_sale_from.insert(dt);
+    /// Start date of sale price, sent as GMT.
+    pub fn date_on_sale_from(&mut self, date: DateTime<Utc>) -> &mut Self {
+        let _ = self.date_on_sale_from_gmt.insert(date.naive_utc());
         self
     }
-    /// End date of sale price, in the site's timezone.
-    pub fn date_on_sale_to(&mut self, year: i32, month: u32, day: u32) -> &mut Self {
-        let dt = chrono::NaiveDate::from_ymd_opt(year, month, day)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap();
-        let _ = self.date_on_sale_to.insert(dt);
+    /// End date of sale price, sent as GMT.
+    pub fn date_on_sale_to(&mut self, date: DateTime<Utc>) -> &mut Self {
+        let _ = self.date_on_sale_to_gmt.insert(date.naive_utc());
         self
     }
     /// If the product is virtual. Default is false.
@@ -343,7 +350,16 @@ impl ProductModifyBuilder {
     /// List of images.
     pub fn images(&mut self, img_src: impl Into<String>) -> &mut Self {
         self.images.get_or_insert(vec![]).push(ImageDTO {
-            src: img_src.into(),
+            id: None,
+            src: Some(img_src.into()),
+        });
+        self
+    }
+    /// List of images, by attachment ID of an uploaded image.
+    pub fn image_id(&mut self, attachment_id: i32) -> &mut Self {
+        self.images.get_or_insert(vec![]).push(ImageDTO {
+            id: Some(attachment_id),
+            src: None,
         });
         self
     }
@@ -389,6 +405,11 @@ impl ProductModifyBuilder {
         });
         self
     }
+    /// Meta data changes made with [`MetaBag`], including removed entries.
+    pub fn meta_bag(&mut self, meta_bag: &MetaBag) -> &mut Self {
+        meta_bag.extend_into(&mut self.meta_data);
+        self
+    }
     pub fn build(&self) -> ProductModify {
         ProductModify {
             id: self.id,
@@ -404,8 +425,8 @@ impl ProductModifyBuilder {
             sku: self.sku.clone(),
             regular_price: self.regular_price.clone(),
             sale_price: self.sale_price.clone(),
-            date_on_sale_from: self.date_on_sale_from,
-            date_on_sale_to: self.date_on_sale_to,
+            date_on_sale_from_gmt: self.date_on_sale_from_gmt,
+            date_on_sale_to_gmt: self.date_on_sale_to_gmt,
             is_virtual: self.is_virtual,
             downloadable: self.downloadable,
             downloads: self.downloads.clone(),
@@ -463,9 +484,13 @@ pub struct CategoryDTO {
 pub struct TagDTO {
     pub id: i32,
 }
+#[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageDTO {
-    pub src: String,
+    /// Attachment ID of an uploaded image.
+    pub id: Option<i32>,
+    /// Image URL, sideloaded by WooCommerce.
+    pub src: Option<String>,
     // pub name: String,
     // pub alt: String,
 }
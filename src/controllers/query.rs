@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Query parameters for listing entities.
+#[derive(Debug, Clone, Default)]
+pub struct ListQuery {
+    params: Vec<(String, String)>,
+}
+impl ListQuery {
+    pub fn new() -> Self {
+        ListQuery::default()
+    }
+    /// Limit results to those matching a string.
+    pub fn search(self, search: impl Into<String>) -> Self {
+        self.param("search", search.into())
+    }
+    /// Limit response to resources published after a given date.
+    pub fn after(self, date: DateTime<Utc>) -> Self {
+        self.date("after", date)
+    }
+    /// Limit response to resources published before a given date.
+    pub fn before(self, date: DateTime<Utc>) -> Self {
+        self.date("before", date)
+    }
+    /// Limit response to resources modified after a given date.
+    pub fn modified_after(self, date: DateTime<Utc>) -> Self {
+        self.date("modified_after", date)
+    }
+    /// Limit response to resources modified before a given date.
+    pub fn modified_before(self, date: DateTime<Utc>) -> Self {
+        self.date("modified_before", date)
+    }
+    /// Any other filter supported by the endpoint, e.g. `sku`, `status` or `code`.
+    pub fn param(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        let key = key.into();
+        self.params.retain(|(k, _)| k != &key);
+        self.params.push((key, value.to_string()));
+        self
+    }
+    fn date(self, key: &str, date: DateTime<Utc>) -> Self {
+        self.param(key, date.format(DATE_FORMAT))
+            .param("dates_are_gmt", true)
+    }
+    pub(crate) fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+}
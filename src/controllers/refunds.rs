@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
-use crate::MetaData;
+use crate::{ApiClient, MetaData, Order, OrderRefundLineItem, Refund};
 
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +38,14 @@ pub struct OrderRefundLineItemCreate {
     total_tax: Option<String>,
     meta_data: Option<Vec<MetaData>>,
     refund_total: Option<f64>,
+    refund_tax: Option<Vec<RefundTax>>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundTax {
+    /// Tax rate ID.
+    pub id: i32,
+    /// The amount to refund for this tax.
+    pub refund_total: f64,
 }
 impl OrderRefundLineItemCreate {
     pub fn builder() -> OrderRefundLineItemCreateBuilder<NoId, NoQuantity> {
@@ -204,6 +215,8 @@ pub struct OrderRefundLineItemCreateBuilder<I, Q> {
     pub meta_data: Option<Vec<MetaData>>,
     // The amount to refund for this line item, excluding taxes.
     pub refund_total: Option<f64>,
+    /// The amounts to refund for the taxes of this line item, by tax rate.
+    pub refund_tax: Option<Vec<RefundTax>>,
 }
 impl<I, Q> OrderRefundLineItemCreateBuilder<I, Q> {
     /// Item ID
@@ -218,23 +231,9 @@ impl<I, Q> OrderRefundLineItemCreateBuilder<I, Q> {
             total_tax: self.total_tax,
             meta_data: self.meta_data,
             refund_total: self.refund_total,
+            refund_tax: self.refund_tax,
         }
     }
-    /// Product name.
-    pub fn name(mut self, name: impl Into<String>) -> Self {
-        let _ = self.name.insert(name.into());
-        self
-    }
-    /// Product ID.
-    pub fn product_id(mut self, product_id: i32) -> Self {
-        let _ = self.product_id.insert(product_id);
-        self
-    }
-    /// Variation ID, if applicable.    
-    pub fn variation_id(mut self, variation_id: i32) -> Self {
-        let _ = self.variation_id.insert(variation_id);
-        self
-    }
     /// Quantity ordered.
     pub fn quantity(self, quantity: i32) -> OrderRefundLineItemCreateBuilder<I, WithQuantity> {
         OrderRefundLineItemCreateBuilder {
@@ -247,32 +246,22 @@ impl<I, Q> OrderRefundLineItemCreateBuilder<I, Q> {
             total_tax: self.total_tax,
             meta_data: self.meta_data,
             refund_total: self.refund_total,
+            refund_tax: self.refund_tax,
         }
     }
-    /// Line total (after discounts).    
-    pub fn total(mut self, total: impl Into<String>) -> Self {
-        let _ = self.total.insert(total.into());
-        self
-    }
-    /// Line total tax (after discounts).
-    pub fn total_tax(mut self, total_tax: impl Into<String>) -> Self {
-        let _ = self.total_tax.insert(total_tax.into());
-        self
-    }
-    /// Meta data.
-    pub fn meta_data(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
-        self.meta_data.get_or_insert(vec![]).push(MetaData {
-            id: None,
-            key: key.into(),
-            value: serde_json::json!(value),
-        });
-        self
-    }
     // The amount to refund for this line item, excluding taxes.
     pub fn refund_total(mut self, refund_total: f64) -> Self {
         let _ = self.refund_total.insert(refund_total);
         self
     }
+    /// The amount to refund for a tax of this line item, by tax rate ID.
+    pub fn refund_tax(mut self, rate_id: i32, refund_total: f64) -> Self {
+        self.refund_tax.get_or_insert(vec![]).push(RefundTax {
+            id: rate_id,
+            refund_total,
+        });
+        self
+    }
 }
 impl OrderRefundLineItemCreateBuilder<WithId, WithQuantity> {
     pub fn build(self) -> OrderRefundLineItemCreate {
@@ -286,6 +275,365 @@ impl OrderRefundLineItemCreateBuilder<WithId, WithQuantity> {
             total_tax: self.total_tax,
             meta_data: self.meta_data,
             refund_total: self.refund_total,
+            refund_tax: self.refund_tax,
+        }
+    }
+}
+/// Refund of an order built from its lines, see [`Refund::for_order`](crate::Refund::for_order).
+/// Totals and taxes are taken from the order, proportionally to the refunded quantity.
+/// The refund total can't exceed what remains of the order total after its previous refunds.
+#[derive(Debug, Clone)]
+pub struct OrderRefundBuilder {
+    order: Order,
+    refunded: BTreeMap<i32, RefundedLine>,
+    refunded_total: f64,
+    line_items: Vec<OrderRefundLineItemCreate>,
+    errors: Vec<String>,
+    reason: Option<String>,
+    refunded_by: Option<i32>,
+    api_refund: bool,
+    api_restock: bool,
+}
+/// Quantity, total and taxes by rate already refunded for an order line.
+#[derive(Debug, Clone, Default)]
+struct RefundedLine {
+    quantity: i32,
+    total: f64,
+    taxes: BTreeMap<i32, f64>,
+}
+/// Order line to refund from, with its remaining quantity and amounts.
+struct RemainingLine {
+    quantity: i32,
+    total: f64,
+    taxes: Vec<(i32, f64)>,
+}
+impl OrderRefundBuilder {
+    pub(crate) fn new(order: &Order) -> Self {
+        let refunded_total = order.refunds.iter().map(|r| amount(&r.total).abs()).sum();
+        OrderRefundBuilder {
+            order: order.to_owned(),
+            refunded: BTreeMap::new(),
+            refunded_total,
+            line_items: vec![],
+            errors: vec![],
+            reason: None,
+            refunded_by: None,
+            api_refund: false,
+            api_restock: true,
         }
     }
+    /// Refunds already made for the order, so only what remains of each line can be refunded.
+    /// The refunds must be listed with their hidden item meta, as [`ApiClient::order_refunds`] does,
+    /// to know which order line each refund line refers to.
+    pub fn previous_refunds(mut self, refunds: &[Refund]) -> Self {
+        for refund in refunds {
+            let other_lines = ["shipping_lines", "fee_lines"]
+                .iter()
+                .filter_map(|key| refund.extra.get(*key))
+                .filter_map(|lines| {
+                    serde_json::from_value::<Vec<OrderRefundLineItem>>(lines.to_owned()).ok()
+                })
+                .flatten();
+            for line in refund.line_items.iter().cloned().chain(other_lines) {
+                let Some(item_id) = refunded_item_id(&line.meta_data) else {
+                    if line.quantity != 0 || amount(&line.total) != 0.0 {
+                        self.errors.push(format!(
+                            "Line {} of refund {} has no _refunded_item_id meta",
+                            line.id, refund.id
+                        ));
+                    }
+                    continue;
+                };
+                let refunded = self.refunded.entry(item_id).or_default();
+                refunded.quantity += line.quantity.abs();
+                refunded.total += amount(&line.total).abs();
+                for tax in &line.taxes {
+                    *refunded.taxes.entry(tax.id).or_default() += amount(&tax.total).abs();
+                }
+            }
+        }
+        self
+    }
+    /// Refund a quantity of a line item. Total and taxes are refunded proportionally.
+    pub fn item(mut self, line_item_id: i32, quantity: i32) -> Self {
+        let Some(item) = self.order.line_items.iter().find(|i| i.id == line_item_id) else {
+            self.errors
+                .push(format!("Line item {line_item_id} is not in the order"));
+            return self;
+        };
+        let line = RemainingLine {
+            quantity: item.quantity,
+            total: amount(&item.total),
+            taxes: item
+                .taxes
+                .iter()
+                .map(|t| (t.id, amount(&t.total)))
+                .collect(),
+        };
+        self.refund_line(line_item_id, line, quantity);
+        self
+    }
+    /// Refund the remaining amount of a shipping line, with its taxes.
+    pub fn shipping(mut self, shipping_line_id: i32) -> Self {
+        match self
+            .order
+            .shipping_lines
+            .iter()
+            .find(|s| s.id == shipping_line_id)
+        {
+            Some(shipping) => {
+                let line = RemainingLine {
+                    quantity: 0,
+                    total: amount(&shipping.total),
+                    taxes: shipping
+                        .taxes
+                        .iter()
+                        .map(|t| (t.id, amount(&t.total)))
+                        .collect(),
+                };
+                self.refund_line(shipping_line_id, line, 0);
+            }
+            None => self.errors.push(format!(
+                "Shipping line {shipping_line_id} is not in the order"
+            )),
+        }
+        self
+    }
+    /// Refund the remaining amount of a fee line, with its taxes.
+    pub fn fee(mut self, fee_line_id: i32) -> Self {
+        match self.order.fee_lines.iter().find(|f| f.id == fee_line_id) {
+            Some(fee) => {
+                let line = RemainingLine {
+                    quantity: 0,
+                    total: amount(&fee.total),
+                    taxes: fee.taxes.iter().map(|t| (t.id, amount(&t.total))).collect(),
+                };
+                self.refund_line(fee_line_id, line, 0);
+            }
+            None => self
+                .errors
+                .push(format!("Fee line {fee_line_id} is not in the order")),
+        }
+        self
+    }
+    /// Reason for refund.
+    pub fn reason(mut self, reason: impl Into<String>) -> Self {
+        let _ = self.reason.insert(reason.into());
+        self
+    }
+    /// User ID of user who created the refund.
+    pub fn refunded_by(mut self, refunded_by: i32) -> Self {
+        let _ = self.refunded_by.insert(refunded_by);
+        self
+    }
+    /// Refund the payment through the payment gateway API. Default is false,
+    /// the refund is only recorded and the money has to be returned manually.
+    pub fn api_refund(mut self, api_refund: bool) -> Self {
+        self.api_refund = api_refund;
+        self
+    }
+    /// Restock the refunded line items. Default is true.
+    pub fn api_restock(mut self, api_restock: bool) -> Self {
+        self.api_restock = api_restock;
+        self
+    }
+    /// Total refund amount, including taxes.
+    pub fn total(&self) -> f64 {
+        let total: f64 = self
+            .line_items
+            .iter()
+            .map(|line| {
+                line.refund_total.unwrap_or_default()
+                    + line
+                        .refund_tax
+                        .iter()
+                        .flatten()
+                        .map(|t| t.refund_total)
+                        .sum::<f64>()
+            })
+            .sum();
+        round(total)
+    }
+    /// Fails if a line is not in the order, nothing is refunded
+    /// or more than remains is refunded.
+    pub fn build(self) -> Result<RefundCreate> {
+        if !self.errors.is_empty() {
+            return Err(anyhow!(
+                "Invalid refund of order {}: {}",
+                self.order.id,
+                self.errors.join(", ")
+            ));
+        }
+        if self.line_items.is_empty() {
+            return Err(anyhow!("Nothing to refund in order {}", self.order.id));
+        }
+        let remaining = round(amount(&self.order.total) - self.refunded_total);
+        if self.total() > remaining {
+            return Err(anyhow!(
+                "Order {} has {remaining:.2} left to refund, not {:.2}",
+                self.order.id,
+                self.total()
+            ));
+        }
+        Ok(RefundCreate {
+            amount: Some(format!("{:.2}", self.total())),
+            reason: self.reason,
+            refunded_by: self.refunded_by,
+            meta_data: None,
+            line_items: Some(self.line_items),
+            api_refund: Some(self.api_refund),
+            api_restock: Some(self.api_restock),
+        })
+    }
+    /// Quantity 0 refunds the remaining amount of lines without quantity, like shipping and fees.
+    fn refund_line(&mut self, line_id: i32, line: RemainingLine, quantity: i32) {
+        if self.line_items.iter().any(|l| l.id == line_id) {
+            self.errors
+                .push(format!("Line {line_id} is refunded more than once"));
+            return;
+        }
+        let refunded = self.refunded.get(&line_id).cloned().unwrap_or_default();
+        let remaining_quantity = line.quantity - refunded.quantity;
+        if quantity > remaining_quantity || (line.quantity > 0 && quantity <= 0) {
+            self.errors.push(format!(
+                "Line {line_id} has {remaining_quantity} left to refund, not {quantity}"
+            ));
+            return;
+        }
+        // The last units get what remains, so rounding doesn't leave cents behind.
+        let share = |total: f64, refunded_total: f64| {
+            let remaining = total - refunded_total;
+            if quantity == remaining_quantity {
+                round(remaining)
+            } else {
+                round(total / line.quantity as f64 * quantity as f64).min(round(remaining))
+            }
+        };
+        let refund_total = share(line.total, refunded.total);
+        if refund_total <= 0.0 && line.total > 0.0 {
+            self.errors
+                .push(format!("Line {line_id} is already refunded"));
+            return;
+        }
+        let mut line_item = OrderRefundLineItemCreate::builder()
+            .id(line_id)
+            .quantity(quantity)
+            .refund_total(refund_total);
+        for (rate_id, tax) in &line.taxes {
+            let refunded_tax = refunded.taxes.get(rate_id).copied().unwrap_or_default();
+            line_item = line_item.refund_tax(*rate_id, share(*tax, refunded_tax));
+        }
+        self.line_items.push(line_item.build());
+    }
+}
+impl ApiClient {
+    /// This API lists all refunds of an order, with the hidden item meta that links
+    /// refund lines to order lines, for `previous_refunds`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let refunds = client.order_refunds(12345).await?;
+    ///     for refund in refunds {
+    ///         info!("Refund {}: {}", refund.id, refund.reason);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn order_refunds(&self, order_id: i32) -> Result<Vec<Refund>> {
+        self.list_all_subentities_paged(order_id, &[("all_item_meta", "true")])
+            .await
+    }
+}
+/// Order item a refund line refers to, stored by WooCommerce in the `_refunded_item_id` meta.
+fn refunded_item_id(meta_data: &[MetaData]) -> Option<i32> {
+    meta_data
+        .iter()
+        .find(|m| m.key == "_refunded_item_id")
+        .and_then(|m| match &m.value {
+            serde_json::Value::Number(id) => id.as_i64().map(|id| id as i32),
+            serde_json::Value::String(id) => id.parse().ok(),
+            _ => None,
+        })
+}
+fn amount(value: &str) -> f64 {
+    value.parse().unwrap_or_default()
+}
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(refunds: serde_json::Value) -> Order {
+        serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "total": "110.00",
+            "line_items": [
+                {"id": 1, "quantity": 2, "total": "100.00", "taxes": [{"id": 3, "total": "10.00"}]}
+            ],
+            "refunds": refunds
+        }))
+        .unwrap()
+    }
+    fn refund(quantity: i32, meta: serde_json::Value) -> Refund {
+        serde_json::from_value(serde_json::json!({
+            "id": 20,
+            "amount": "55.00",
+            "line_items": [{
+                "id": 21,
+                "quantity": -quantity,
+                "total": "-50.00",
+                "taxes": [{"id": 3, "total": "-5.00"}],
+                "meta_data": meta
+            }]
+        }))
+        .unwrap()
+    }
+    #[test]
+    fn refunds_proportionally() {
+        let refund = Refund::for_order(&order(serde_json::json!([])))
+            .item(1, 1)
+            .build()
+            .unwrap();
+        assert_eq!(refund.amount.as_deref(), Some("55.00"));
+    }
+    #[test]
+    fn total_is_capped_by_order_refunds() {
+        let order = order(serde_json::json!([{"id": 20, "total": "-80.00"}]));
+        let error = Refund::for_order(&order).item(1, 1).build().unwrap_err();
+        assert!(error.to_string().contains("30.00 left to refund"));
+    }
+    #[test]
+    fn lines_are_capped_by_previous_refunds() {
+        let previous = refund(
+            1,
+            serde_json::json!([{"id": 9, "key": "_refunded_item_id", "value": "1"}]),
+        );
+        let builder =
+            Refund::for_order(&order(serde_json::json!([]))).previous_refunds(&[previous]);
+        assert!(builder.clone().item(1, 2).build().is_err());
+        let refund = builder.item(1, 1).build().unwrap();
+        assert_eq!(refund.amount.as_deref(), Some("55.00"));
+    }
+    #[test]
+    fn previous_refunds_without_item_meta_fail() {
+        let previous = refund(1, serde_json::json!([]));
+        let error = Refund::for_order(&order(serde_json::json!([])))
+            .previous_refunds(&[previous])
+            .item(1, 1)
+            .build()
+            .unwrap_err();
+        assert!(error.to_string().contains("_refunded_item_id"));
+    }
 }
@@ -1 +1,98 @@
+use anyhow::{anyhow, Result};
 
+use crate::{
+    controllers::Entity, ApiClient, ReportPeriod, ReportTotals, ReportTotalsKind, SaleReport,
+    TopSellersReport,
+};
+
+impl ApiClient {
+    /// This API lets you retrieve the sales report for a period.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use chrono::NaiveDate;
+    /// use rust_woocommerce::{ApiClient, Config, ReportPeriod};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let last_month = client.sales_report(ReportPeriod::LastMonth).await?;
+    ///     info!("Last month net sales: {}", last_month.net_sales);
+    ///     let period = ReportPeriod::DateRange(
+    ///         NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+    ///         NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+    ///     );
+    ///     let first_quarter = client.sales_report(period).await?;
+    ///     info!("First quarter orders: {}", first_quarter.total_orders);
+    ///     for (month, total) in first_quarter.totals_by_date() {
+    ///         info!("{}: {}", month.format("%B"), total.sales);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sales_report(&self, period: ReportPeriod) -> Result<SaleReport> {
+        let mut uri = self.base_url.join(&SaleReport::endpoint())?;
+        uri.query_pairs_mut().extend_pairs(period.params());
+        let reports: Vec<SaleReport> = self.get_request_with_tries(uri.clone(), 3).await?;
+        reports
+            .into_iter()
+            .next()
+            .ok_or(anyhow!("Empty sales report with uri: {uri}"))
+    }
+    /// This API lets you retrieve the top sellers report for a period.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, ReportPeriod};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let top_sellers = client.top_sellers_report(ReportPeriod::Week).await?;
+    ///     for seller in top_sellers {
+    ///         info!("{} sold {} times", seller.name, seller.quantity);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn top_sellers_report(&self, period: ReportPeriod) -> Result<Vec<TopSellersReport>> {
+        let mut uri = self.base_url.join(&TopSellersReport::endpoint())?;
+        uri.query_pairs_mut().extend_pairs(period.params());
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lets you retrieve the totals reports, e.g. orders by status or customers by type.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, ReportTotalsKind};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let totals = client.report_totals(ReportTotalsKind::Orders).await?;
+    ///     for total in totals {
+    ///         info!("{} orders: {}", total.name, total.total);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn report_totals(&self, kind: ReportTotalsKind) -> Result<Vec<ReportTotals>> {
+        let uri = self.base_url.join(&kind.endpoint())?;
+        self.get_request_with_tries(uri, 3).await
+    }
+}
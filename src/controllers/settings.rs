@@ -1,6 +1,266 @@
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+
+use serde_with::skip_serializing_none;
+
+use super::entities::decode;
+use crate::models::data::decode_html_entities;
+use crate::{
+    controllers::Entity, ApiClient, BatchObject, Currency, SettingGroup, SettingOption,
+    StoreSettings, StoreTimezone, WooError,
+};
+#[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingOptionUpdate {
+    /// A unique identifier for the setting, required for batch update.
+    pub id: Option<String>,
     /// Setting value.
     pub value: serde_json::Value,
 }
+impl SettingOptionUpdate {
+    pub fn new(id: impl Into<String>, value: impl Serialize) -> Self {
+        Self {
+            id: Some(id.into()),
+            value: serde_json::json!(value),
+        }
+    }
+}
+/// Timezone fields of the WordPress REST index.
+#[derive(Debug, Clone, Deserialize)]
+struct WordpressIndex {
+    #[serde(default)]
+    timezone_string: Option<String>,
+    #[serde(default)]
+    gmt_offset: Option<serde_json::Value>,
+}
+impl ApiClient {
+    /// This API reads the store timezone from the WordPress REST index: the named timezone,
+    /// or the manual UTC offset when the store doesn't use a named one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, Order};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let timezone = client.store_timezone().await?;
+    ///     let order = client.retrieve::<Order>(12345).await?;
+    ///     if let Some(created) = order.date_created_utc() {
+    ///         info!("Order created at {}", timezone.to_local(created));
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn store_timezone(&self) -> Result<StoreTimezone> {
+        let uri = self.wp_url("?_fields=timezone_string,gmt_offset")?;
+        let response = self.client.get(uri.clone()).send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        let index: WordpressIndex = decode(&uri, status, &body)?;
+        match (index.timezone_string, index.gmt_offset) {
+            (Some(timezone), _) if !timezone.trim().is_empty() => StoreTimezone::parse(&timezone),
+            (_, Some(offset)) => {
+                let hours = match &offset {
+                    serde_json::Value::Number(n) => n.as_f64(),
+                    serde_json::Value::String(s) => s.trim().parse().ok(),
+                    _ => None,
+                };
+                hours
+                    .ok_or(anyhow!("Invalid GMT offset {offset} with uri: {uri}"))
+                    .and_then(StoreTimezone::from_gmt_offset)
+            }
+            _ => Err(anyhow!("No timezone in the response with uri: {uri}")),
+        }
+    }
+    /// This API lists all setting options of a group, e.g. `general` or `products`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let options = client.list_setting_options("general").await?;
+    ///     for option in options {
+    ///         info!("{}: {}", option.id, option.value);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn list_setting_options(&self, group_id: &str) -> Result<Vec<SettingOption>> {
+        let uri = self
+            .base_url
+            .join(&format!("{}{group_id}", SettingGroup::endpoint()))?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lets you retrieve a setting option by group and option id.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let currency = client
+    ///         .retrieve_setting_option("general", "woocommerce_currency")
+    ///         .await?;
+    ///     info!("Store currency: {}", currency.value);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn retrieve_setting_option(
+        &self,
+        group_id: &str,
+        option_id: &str,
+    ) -> Result<SettingOption> {
+        let uri = self.base_url.join(&format!(
+            "{}{group_id}/{option_id}",
+            SettingGroup::endpoint()
+        ))?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lets you change the value of a setting option.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let updated = client
+    ///         .update_setting_option("general", "woocommerce_calc_taxes", "yes")
+    ///         .await?;
+    ///     info!("Taxes enabled: {}", updated.is_enabled());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update_setting_option(
+        &self,
+        group_id: &str,
+        option_id: &str,
+        value: impl Serialize,
+    ) -> Result<SettingOption> {
+        let uri = self.base_url.join(&format!(
+            "{}{group_id}/{option_id}",
+            SettingGroup::endpoint()
+        ))?;
+        let update = SettingOptionUpdate {
+            id: None,
+            value: serde_json::json!(value),
+        };
+        self.put_request_with_tries(&update, uri, 3).await
+    }
+    /// This API helps you to batch update multiple setting options of a group.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, SettingOption};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let updates = vec![
+    ///         SettingOption::update("woocommerce_currency", "EUR"),
+    ///         SettingOption::update("woocommerce_price_num_decimals", "2"),
+    ///     ];
+    ///     let updated = client
+    ///         .batch_update_setting_options("general", updates)
+    ///         .await?;
+    ///     info!("Updated {} setting options", updated.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn batch_update_setting_options(
+        &self,
+        group_id: &str,
+        updates: Vec<SettingOptionUpdate>,
+    ) -> Result<Vec<SettingOption>> {
+        let uri = self
+            .base_url
+            .join(&format!("{}{group_id}/batch", SettingGroup::endpoint()))?;
+        let batch = BatchObject {
+            create: None,
+            update: Some(updates),
+            delete: None,
+        };
+        let response: BatchObject<SettingOption> =
+            self.post_request_with_tries(&batch, uri, 3).await?;
+        Ok(response.update.unwrap_or_default())
+    }
+    /// This API reads the `general`, `products` and `tax` setting groups, the store currency
+    /// and the timezone into one typed snapshot.
+    /// Fails if any of them can't be read, except the `tax` group while taxes are disabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, Product};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let settings = client.store_settings().await?;
+    ///     let product = client.retrieve::<Product>(12345).await?;
+    ///     let price = product.price.parse::<f64>().unwrap_or_default();
+    ///     info!("{} costs {}", product.name, settings.format_price(price));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn store_settings(&self) -> Result<StoreSettings> {
+        let currency_uri = self.base_url.join("data/currencies/current")?;
+        let (general, products, tax, currency, timezone) = tokio::join!(
+            self.list_setting_options("general"),
+            self.list_setting_options("products"),
+            self.list_setting_options("tax"),
+            self.get_request_with_tries::<Currency>(currency_uri, 3),
+            self.store_timezone(),
+        );
+        let mut options = general?;
+        options.extend(products?);
+        // The tax group is not registered while taxes are disabled.
+        match tax {
+            Ok(tax) => options.extend(tax),
+            Err(e)
+                if e.downcast_ref::<WooError>()
+                    .is_some_and(WooError::is_not_found) => {}
+            Err(e) => return Err(e),
+        }
+        let mut settings = StoreSettings::from_options(&options);
+        settings.currency_symbol = decode_html_entities(&currency?.symbol);
+        settings.timezone = Some(timezone?);
+        Ok(settings)
+    }
+}
@@ -1 +1,140 @@
+use std::collections::BTreeMap;
 
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{
+    controllers::Entity, ApiClient, FlatRateCalculation, FreeShippingRequires, ShippingZoneMethod,
+    TaxStatus,
+};
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ShippingZoneMethodModify {
+    /// Shipping method ID, only sent when the method is added to a zone.
+    method_id: Option<String>,
+    /// Shipping method sort order.
+    order: Option<i32>,
+    /// Shipping method enabled status.
+    enabled: Option<bool>,
+    /// Shipping method settings values, by setting id.
+    settings: Option<BTreeMap<String, String>>,
+}
+impl ShippingZoneMethodModify {
+    /// Shipping method ID, e.g. `flat_rate`, `free_shipping` or `local_pickup`.
+    pub fn new(method_id: impl Into<String>) -> Self {
+        Self {
+            method_id: Some(method_id.into()),
+            ..Default::default()
+        }
+    }
+    /// Sort order of the method in the zone.
+    pub fn order(mut self, order: i32) -> Self {
+        let _ = self.order.insert(order);
+        self
+    }
+    /// Enable shipping method.
+    pub fn enabled(mut self) -> Self {
+        let _ = self.enabled.insert(true);
+        self
+    }
+    /// Disable shipping method.
+    pub fn disabled(mut self) -> Self {
+        let _ = self.enabled.insert(false);
+        self
+    }
+    /// Value of any setting by its id.
+    pub fn setting(mut self, id: impl Into<String>, value: impl Into<String>) -> Self {
+        self.settings
+            .get_or_insert(BTreeMap::new())
+            .insert(id.into(), value.into());
+        self
+    }
+    /// Shipping method customer facing title.
+    pub fn title(self, title: impl Into<String>) -> Self {
+        self.setting("title", title)
+    }
+    /// Flat rate or local pickup cost, flat rate accepts formulas like `10 + [qty] * 2`.
+    pub fn cost(self, cost: impl Into<String>) -> Self {
+        self.setting("cost", cost)
+    }
+    /// Flat rate cost formula for a shipping class.
+    pub fn class_cost(self, shipping_class_id: i32, cost: impl Into<String>) -> Self {
+        self.setting(format!("class_cost_{shipping_class_id}"), cost)
+    }
+    /// Flat rate cost formula for products without a shipping class.
+    pub fn no_class_cost(self, cost: impl Into<String>) -> Self {
+        self.setting("no_class_cost", cost)
+    }
+    /// Flat rate calculation type, per class or per order.
+    pub fn calculation_type(self, calculation_type: FlatRateCalculation) -> Self {
+        self.setting("type", setting_value(calculation_type))
+    }
+    /// Flat rate or local pickup tax status.
+    pub fn tax_status(self, tax_status: TaxStatus) -> Self {
+        self.setting("tax_status", setting_value(tax_status))
+    }
+    /// Condition to make free shipping available.
+    pub fn requires(self, requires: FreeShippingRequires) -> Self {
+        self.setting("requires", setting_value(requires))
+    }
+    /// Minimum order amount for free shipping.
+    pub fn min_amount(self, min_amount: impl Into<String>) -> Self {
+        self.setting("min_amount", min_amount)
+    }
+    /// Check the free shipping minimum amount before coupon discounts.
+    pub fn ignore_discounts(self, ignore_discounts: bool) -> Self {
+        self.setting(
+            "ignore_discounts",
+            if ignore_discounts { "yes" } else { "no" },
+        )
+    }
+}
+/// Enums are sent with their serialized names, e.g. `taxable` or `min_amount`.
+fn setting_value(value: impl Serialize) -> String {
+    match serde_json::json!(value) {
+        serde_json::Value::String(value) => value,
+        value => value.to_string(),
+    }
+}
+impl ApiClient {
+    /// This API sets the order of the shipping methods of a zone to the given instance ids order.
+    /// Methods not in the list keep their order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let methods = client.reorder_shipping_zone_methods(5, &[26, 12, 7]).await?;
+    ///     for method in methods {
+    ///         info!("{}: {}", method.order, method.title);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn reorder_shipping_zone_methods(
+        &self,
+        zone_id: i32,
+        instance_ids: &[i32],
+    ) -> Result<Vec<ShippingZoneMethod>> {
+        let mut result = Vec::new();
+        for (order, instance_id) in instance_ids.iter().enumerate() {
+            let update = ShippingZoneMethod::update().order(order as i32);
+            let uri = self
+                .base_url
+                .join(&ShippingZoneMethod::child_endpoint(zone_id))?
+                .join(&instance_id.to_string())?;
+            result.push(self.put_request_with_tries(&update, uri, 3).await?);
+        }
+        Ok(result)
+    }
+}
@@ -1,5 +1,10 @@
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+
+use crate::{
+    ApiClient, ShippingZone, ShippingZoneLocation, ShippingZoneMethod, ShippingZoneResolver,
+};
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShippingZoneCreate {
@@ -44,3 +49,46 @@ impl ShippingZoneUpdate {
         self
     }
 }
+impl ApiClient {
+    /// This API fetches all shipping zones with their locations and methods,
+    /// to resolve shipping zones of destinations offline.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let resolver = client.shipping_zone_resolver().await?;
+    ///     if let Some(resolved) = resolver.resolve("US", "CA", "90210") {
+    ///         info!("Zone {} has {} methods", resolved.zone.name, resolved.methods.len());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn shipping_zone_resolver(&self) -> Result<ShippingZoneResolver> {
+        let (zones, continents) = tokio::join!(self.list_all::<ShippingZone>(), self.continents());
+        let mut resolver = ShippingZoneResolver::new().continents(&continents?);
+        for zone in zones? {
+            let (locations, methods) = tokio::join!(
+                self.zone_locations(zone.id),
+                self.list_all_subentities::<ShippingZoneMethod>(zone.id)
+            );
+            resolver = resolver.zone(zone, locations?, methods?);
+        }
+        Ok(resolver)
+    }
+    /// Zone 0 has no locations endpoint.
+    async fn zone_locations(&self, zone_id: i32) -> Result<Vec<ShippingZoneLocation>> {
+        match zone_id {
+            0 => Ok(vec![]),
+            _ => self.list_all_subentities(zone_id).await,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{controllers::Entity, ApiClient, SystemStatus, SystemStatusTool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RunTool {
+    confirm: bool,
+}
+impl ApiClient {
+    /// This API lets you retrieve the system status report: environment, database,
+    /// plugins, theme, settings and pages.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let status = client.system_status().await?;
+    ///     info!(
+    ///         "WooCommerce {} on WordPress {}, HPOS enabled: {}",
+    ///         status.environment.version,
+    ///         status.environment.wp_version,
+    ///         status.settings.hpos_enabled
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn system_status(&self) -> Result<SystemStatus> {
+        let uri = self.base_url.join(&SystemStatus::endpoint())?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API lists all the system status tools.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let tools = client.system_status_tools().await?;
+    ///     for tool in tools {
+    ///         info!("{}: {}", tool.id, tool.description);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn system_status_tools(&self) -> Result<Vec<SystemStatusTool>> {
+        let uri = self.base_url.join(&SystemStatusTool::endpoint())?;
+        self.get_request_with_tries(uri, 3).await
+    }
+    /// This API runs a system status tool, e.g. `clear_transients` or `recount_terms`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let tool = client.run_tool("clear_transients").await?;
+    ///     info!("{}: {:?}", tool.name, tool.message);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_tool(&self, tool_id: &str) -> Result<SystemStatusTool> {
+        let uri = self
+            .base_url
+            .join(&SystemStatusTool::endpoint())?
+            .join(tool_id)?;
+        self.put_request_with_tries(&RunTool { confirm: true }, uri, 3)
+            .await
+    }
+}
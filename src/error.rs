@@ -1,6 +1,101 @@
-// #[derive(Debug)]
-// pub enum Error {
-//     Custom(String),
-// }
-pub type Error = Box<dyn std::error::Error>;
-pub type Result<T> = core::result::Result<T, Error>;
+use std::fmt::Display;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Error response of the WooCommerce API.
+/// Returned inside [`anyhow::Error`], use `error.downcast_ref::<WooError>()` to inspect it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WooError {
+    /// Error code.
+    pub code: WooErrorCode,
+    /// Error message.
+    pub message: String,
+    /// HTTP status of the response.
+    pub status: u16,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ErrorResponse {
+    #[serde(default)]
+    code: Option<WooErrorCode>,
+    #[serde(default)]
+    message: String,
+}
+impl WooError {
+    pub(crate) fn from_response(status: StatusCode, body: &str) -> Self {
+        match serde_json::from_str::<ErrorResponse>(body) {
+            Ok(ErrorResponse {
+                code: Some(code),
+                message,
+            }) => WooError {
+                code,
+                message,
+                status: status.as_u16(),
+            },
+            _ => WooError {
+                code: WooErrorCode::Other(String::from("http_error")),
+                message: body.chars().take(500).collect(),
+                status: status.as_u16(),
+            },
+        }
+    }
+    /// True if the resource doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        self.status == 404
+    }
+}
+impl Display for WooError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "WooCommerce error {} ({}): {}",
+            self.code, self.status, self.message
+        )
+    }
+}
+impl std::error::Error for WooError {}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WooErrorCode {
+    /// Route doesn't exist, e.g. the endpoint needs a newer WooCommerce version.
+    RestNoRoute,
+    /// Invalid or missing request parameter.
+    RestInvalidParam,
+    /// Required parameter is missing.
+    RestMissingCallbackParam,
+    /// Consumer key or secret is invalid.
+    WoocommerceRestAuthenticationError,
+    /// Not allowed to list resources.
+    WoocommerceRestCannotView,
+    /// Not allowed to create resources.
+    WoocommerceRestCannotCreate,
+    /// Not allowed to edit resources.
+    WoocommerceRestCannotEdit,
+    /// Not allowed to delete resources.
+    WoocommerceRestCannotDelete,
+    /// Resource doesn't exist.
+    WoocommerceRestNotFound,
+    /// Invalid resource ID.
+    WoocommerceRestInvalidId,
+    /// Invalid order ID.
+    WoocommerceRestShopOrderInvalidId,
+    /// Invalid product ID.
+    WoocommerceRestProductInvalidId,
+    /// SKU is already used by another product.
+    ProductInvalidSku,
+    /// Order has no billing email to send the email to.
+    WoocommerceRestMissingEmail,
+    /// Email template doesn't exist or doesn't apply to the order.
+    WoocommerceRestInvalidEmailTemplate,
+    /// Any other code, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+impl Display for WooErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(code)) => write!(f, "{code}"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
@@ -4,11 +4,16 @@
 
 mod controllers;
 mod models;
-pub use self::controllers::ApiClient;
+pub use self::controllers::{query::ListQuery, ApiClient};
 pub use models::{
-    coupons::*, customers::*, data::*, orders::*, product_attributes::*, product_categories::*,
-    product_reviews::*, product_variations::*, products::*, reports::*, webhooks::*, BatchObject,
-    MetaData,
+    cart::*, coupon_codes::*, coupon_validator::*, coupons::*, customers::*, data::*, flat_rate::*,
+    media::*, order_notes::*, order_totals::*, order_workflow::*, orders::*, payment_gateways::*,
+    product_attributes::*, product_categories::*, product_reviews::*, product_variations::*,
+    products::*, refunds::*, reports::*, settings::*, shipping_zone_locations::*,
+    shipping_zone_methods::*, shipping_zone_resolver::*, shipping_zones::*, store_settings::*,
+    system_status::*, tax_engine::*, tax_rates::*, webhooks::*, BatchObject, MetaBag, MetaData,
 };
 mod config;
+mod error;
 pub use config::Config;
+pub use error::{WooError, WooErrorCode};
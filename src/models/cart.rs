@@ -0,0 +1,747 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+use crate::controllers::orders::{
+    CreateOrder, OrderFeeLineCreate, OrderLineItemCreate, ShippingLineCreate,
+};
+
+use super::{
+    coupon_validator::CouponValidator,
+    coupons::Coupon,
+    customers::{Billing, Customer, Shipping},
+    flat_rate::FlatRatePackage,
+    orders::Order,
+    product_variations::{ManageStock, ProductVariation},
+    products::{Product, ProductType, StockStatus},
+    shipping_zone_methods::{
+        FlatRateSettings, FreeShippingRequires, FreeShippingSettings, ShippingMethodConfig,
+        ShippingZoneMethod,
+    },
+    shipping_zone_resolver::ResolvedShippingZone,
+};
+
+/// Shopping cart that becomes a `CreateOrder` for
+/// [`ApiClient::create`](crate::ApiClient::create).
+/// Products added by ID or SKU and coupons added by code are loaded with
+/// [`ApiClient::resolve_cart`](crate::ApiClient::resolve_cart).
+///
+/// # Example
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use rust_woocommerce::{ApiClient, Cart, Config, Customer, Order};
+/// use tracing::info;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     tracing_subscriber::fmt::init();
+///     let config = Config::new("woo.toml")?;
+///     let client = ApiClient::new(&config)?;
+///     let customer = client.retrieve::<Customer>(25).await?;
+///     let zone = client
+///         .shipping_zone_resolver()
+///         .await?
+///         .resolve(&customer.shipping.country, &customer.shipping.state, &customer.shipping.postcode);
+///     let mut cart = Cart::new()
+///         .product_id(3982, 2)
+///         .sku("MUG-BLUE", 1)
+///         .coupon("summer-sale")
+///         .fee("Gift wrapping", 2.5)
+///         .customer(&customer);
+///     if let Some(zone) = &zone {
+///         let method = zone.methods.first().map(|m| m.instance_id).unwrap_or_default();
+///         cart = cart.shipping(zone, method);
+///     }
+///     let cart = client.resolve_cart(cart).await?;
+///     let order = client.create::<Order>(cart.to_order()?).await?;
+///     info!("Order {} created, total {}", order.id, order.total);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cart {
+    pub(crate) items: Vec<CartItem>,
+    coupons: Vec<String>,
+    pub(crate) loaded_coupons: Vec<Coupon>,
+    fees: Vec<OrderFeeLineCreate>,
+    shipping: Option<CartShipping>,
+    shipping_cost: Option<f64>,
+    customer_id: Option<i32>,
+    billing: Option<Billing>,
+    shipping_address: Option<Shipping>,
+    customer_note: Option<String>,
+    decimals: u32,
+}
+/// Item of a cart, loaded or still to load by ID or SKU.
+#[derive(Debug, Clone)]
+pub(crate) enum CartItem {
+    Loaded {
+        product: Box<Product>,
+        variation: Option<Box<ProductVariation>>,
+        quantity: i32,
+    },
+    Id {
+        id: i32,
+        quantity: i32,
+    },
+    Sku {
+        sku: String,
+        quantity: i32,
+    },
+}
+#[derive(Debug, Clone)]
+struct CartShipping {
+    instance_id: i32,
+    method: Option<ShippingZoneMethod>,
+}
+/// Reason a cart can't be ordered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CartProblem {
+    /// Cart without items.
+    Empty,
+    /// Product added by ID or SKU and not loaded yet.
+    NotLoaded(String),
+    /// Variable product added without choosing a variation, by product ID.
+    VariationRequired(i32),
+    /// Product or variation that can't be bought, by ID.
+    NotPurchasable(i32),
+    /// Product or variation out of stock, by ID.
+    OutOfStock(i32),
+    /// Not enough stock and no backorders allowed.
+    InsufficientStock {
+        /// ID of the product or variation that manages the stock.
+        id: i32,
+        /// Quantity in the cart, of all variations sharing the stock.
+        requested: i32,
+        /// Quantity in stock.
+        available: i32,
+    },
+    /// Shipping method not enabled in the zone, by instance ID.
+    UnknownShippingMethod(i32),
+}
+impl Display for CartProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CartProblem::Empty => write!(f, "Cart is empty"),
+            CartProblem::NotLoaded(product) => write!(f, "Product {product} is not loaded"),
+            CartProblem::VariationRequired(id) => {
+                write!(f, "Product {id} is variable, choose a variation")
+            }
+            CartProblem::NotPurchasable(id) => write!(f, "Product {id} can't be purchased"),
+            CartProblem::OutOfStock(id) => write!(f, "Product {id} is out of stock"),
+            CartProblem::InsufficientStock {
+                id,
+                requested,
+                available,
+            } => write!(
+                f,
+                "Product {id} has {available} in stock, {requested} requested"
+            ),
+            CartProblem::UnknownShippingMethod(instance_id) => {
+                write!(f, "Shipping method {instance_id} is not available")
+            }
+        }
+    }
+}
+impl Default for Cart {
+    fn default() -> Self {
+        Cart {
+            items: vec![],
+            coupons: vec![],
+            loaded_coupons: vec![],
+            fees: vec![],
+            shipping: None,
+            shipping_cost: None,
+            customer_id: None,
+            billing: None,
+            shipping_address: None,
+            customer_note: None,
+            decimals: 2,
+        }
+    }
+}
+impl Cart {
+    pub fn new() -> Self {
+        Cart::default()
+    }
+    /// Product with its quantity.
+    pub fn product(mut self, product: &Product, quantity: i32) -> Self {
+        self.items.push(CartItem::Loaded {
+            product: Box::new(product.to_owned()),
+            variation: None,
+            quantity,
+        });
+        self
+    }
+    /// Variation of a variable product with its quantity.
+    pub fn variation(
+        mut self,
+        product: &Product,
+        variation: &ProductVariation,
+        quantity: i32,
+    ) -> Self {
+        self.items.push(CartItem::Loaded {
+            product: Box::new(product.to_owned()),
+            variation: Some(Box::new(variation.to_owned())),
+            quantity,
+        });
+        self
+    }
+    /// Product or variation by ID, loaded with [`ApiClient::resolve_cart`](crate::ApiClient::resolve_cart).
+    pub fn product_id(mut self, id: i32, quantity: i32) -> Self {
+        self.items.push(CartItem::Id { id, quantity });
+        self
+    }
+    /// Product or variation by SKU, loaded with [`ApiClient::resolve_cart`](crate::ApiClient::resolve_cart).
+    pub fn sku(mut self, sku: impl Into<String>, quantity: i32) -> Self {
+        self.items.push(CartItem::Sku {
+            sku: sku.into(),
+            quantity,
+        });
+        self
+    }
+    /// Coupon code, applied by WooCommerce when the order is created.
+    pub fn coupon(mut self, code: impl Into<String>) -> Self {
+        let code = code.into();
+        if !self.coupons.iter().any(|c| c.eq_ignore_ascii_case(&code)) {
+            self.coupons.push(code);
+        }
+        self
+    }
+    /// Coupon with its settings, so free shipping requirements can be checked offline.
+    pub fn loaded_coupon(mut self, coupon: &Coupon) -> Self {
+        self.loaded_coupons
+            .retain(|c| !c.code.eq_ignore_ascii_case(&coupon.code));
+        self.loaded_coupons.push(coupon.to_owned());
+        self.coupon(&coupon.code)
+    }
+    /// Codes of the coupons added without their settings.
+    pub(crate) fn coupons_to_load(&self) -> Vec<String> {
+        self.coupons
+            .iter()
+            .filter(|code| {
+                !self
+                    .loaded_coupons
+                    .iter()
+                    .any(|c| c.code.eq_ignore_ascii_case(code))
+            })
+            .cloned()
+            .collect()
+    }
+    /// Taxable fee in the standard tax class.
+    pub fn fee(self, name: impl Into<String>, total: f64) -> Self {
+        let total = format!("{:.*}", self.decimals as usize, total);
+        self.fee_line(OrderFeeLineCreate::new(name, total))
+    }
+    /// Fee with all its details, see [`Order::fee_line`].
+    pub fn fee_line(mut self, fee: OrderFeeLineCreate) -> Self {
+        self.fees.push(fee);
+        self
+    }
+    /// Shipping method of the resolved zone by instance ID. The cost is calculated from the
+    /// method settings: flat rate formulas, local pickup cost or free shipping if the cart
+    /// meets its requirements.
+    pub fn shipping(mut self, zone: &ResolvedShippingZone, instance_id: i32) -> Self {
+        let method = zone
+            .methods
+            .iter()
+            .find(|m| m.instance_id == instance_id)
+            .cloned();
+        self.shipping = Some(CartShipping {
+            instance_id,
+            method,
+        });
+        self
+    }
+    /// Shipping cost to charge instead of the one calculated from the method settings,
+    /// e.g. for methods added by plugins.
+    pub fn shipping_cost(mut self, cost: f64) -> Self {
+        let _ = self.shipping_cost.insert(cost);
+        self
+    }
+    /// Customer ID with billing and shipping addresses of the customer.
+    pub fn customer(mut self, customer: &Customer) -> Self {
+        let _ = self.customer_id.insert(customer.id);
+        let _ = self.billing.insert(customer.billing.to_owned());
+        let _ = self.shipping_address.insert(customer.shipping.to_owned());
+        self
+    }
+    /// Billing address. A billing email is required to create the order.
+    pub fn billing(mut self, billing: Billing) -> Self {
+        let _ = self.billing.insert(billing);
+        self
+    }
+    /// Shipping address.
+    pub fn shipping_address(mut self, shipping: Shipping) -> Self {
+        let _ = self.shipping_address.insert(shipping);
+        self
+    }
+    /// Note left by the customer during checkout.
+    pub fn customer_note(mut self, note: impl Into<String>) -> Self {
+        let _ = self.customer_note.insert(note.into());
+        self
+    }
+    /// Number of decimals of fee and shipping totals. Default is 2.
+    pub fn decimals(mut self, decimals: u32) -> Self {
+        self.decimals = decimals;
+        self
+    }
+    /// Reasons the cart can't be ordered, empty if it can.
+    pub fn problems(&self) -> Vec<CartProblem> {
+        let mut problems = vec![];
+        if self.items.is_empty() {
+            problems.push(CartProblem::Empty);
+        }
+        for item in &self.items {
+            match item {
+                CartItem::Id { id, .. } => problems.push(CartProblem::NotLoaded(id.to_string())),
+                CartItem::Sku { sku, .. } => problems.push(CartProblem::NotLoaded(sku.to_owned())),
+                CartItem::Loaded { .. } => {}
+            }
+        }
+        let lines = self.lines();
+        for (product, variation, quantity) in &lines {
+            // Variations using the stock of the product share it with each other.
+            let quantity = match variation.map(|v| &v.manage_stock) {
+                Some(ManageStock::Parent(_)) => lines
+                    .iter()
+                    .filter(|(p, v, _)| {
+                        p.id == product.id
+                            && v.is_some_and(|v| matches!(v.manage_stock, ManageStock::Parent(_)))
+                    })
+                    .map(|(_, _, quantity)| quantity)
+                    .sum(),
+                _ => *quantity,
+            };
+            if let Some(problem) = stock_problem(product, *variation, quantity) {
+                if !problems.contains(&problem) {
+                    problems.push(problem);
+                }
+            }
+        }
+        if let Some(CartShipping {
+            instance_id,
+            method: None,
+            ..
+        }) = &self.shipping
+        {
+            problems.push(CartProblem::UnknownShippingMethod(*instance_id));
+        }
+        problems
+    }
+    /// Shipping cost of the chosen method for the items that need shipping.
+    /// Fails if the cart doesn't meet the requirements of free shipping, and for methods
+    /// other than flat rate, free shipping and local pickup without a
+    /// [`shipping_cost`](Cart::shipping_cost).
+    pub fn shipping_total(&self) -> Result<f64> {
+        let Some(CartShipping {
+            method: Some(method),
+            ..
+        }) = &self.shipping
+        else {
+            return Ok(0.0);
+        };
+        if let Some(cost) = self.shipping_cost {
+            return Ok(cost);
+        }
+        let package = self
+            .lines()
+            .into_iter()
+            .filter(|(product, variation, _)| match variation {
+                Some(variation) => !variation.is_virtual,
+                None => !product.is_virtual,
+            })
+            .fold(
+                FlatRatePackage::new(),
+                |package, (product, variation, quantity)| match variation {
+                    Some(variation) => package.variation(product, variation, quantity),
+                    None => package.product(product, quantity),
+                },
+            );
+        Ok(match method.typed_settings() {
+            ShippingMethodConfig::FlatRate(settings) => {
+                settings.package_cost(&package)?.unwrap_or_default()
+            }
+            ShippingMethodConfig::LocalPickup(settings) => {
+                FlatRateSettings::evaluate_cost(&settings.cost, package.quantity(), package.cost())?
+            }
+            ShippingMethodConfig::FreeShipping(settings) => match self.free_shipping(&settings)? {
+                true => 0.0,
+                false => {
+                    return Err(anyhow!(
+                        "Free shipping {} is not available for the cart",
+                        method.instance_id
+                    ))
+                }
+            },
+            ShippingMethodConfig::Other(_) => {
+                return Err(anyhow!(
+                    "Shipping method {} is {}, set the shipping cost",
+                    method.instance_id,
+                    method.method_id
+                ))
+            }
+        })
+    }
+    /// True if the cart meets the free shipping requirements, like WooCommerce
+    /// `WC_Shipping_Free_Shipping::is_available`.
+    fn free_shipping(&self, settings: &FreeShippingSettings) -> Result<bool> {
+        if settings.requires == FreeShippingRequires::Nothing {
+            return Ok(true);
+        }
+        let not_loaded = self.coupons_to_load();
+        if !not_loaded.is_empty() {
+            return Err(anyhow!(
+                "Coupons {} are not loaded, free shipping can't be checked",
+                not_loaded.join(", ")
+            ));
+        }
+        let mut validator = self.lines().into_iter().fold(
+            CouponValidator::new(),
+            |validator, (product, variation, quantity)| match variation {
+                Some(variation) => validator.variation(product, variation, quantity),
+                None => validator.product(product, quantity),
+            },
+        );
+        if let Some(billing) = &self.billing {
+            validator = validator.email(&billing.email);
+        }
+        let coupons = self
+            .coupons
+            .iter()
+            .filter_map(|code| {
+                self.loaded_coupons
+                    .iter()
+                    .find(|c| c.code.eq_ignore_ascii_case(code))
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+        let applied = validator.apply(&coupons);
+        let has_coupon = applied
+            .coupons
+            .iter()
+            .zip(&coupons)
+            .any(|(check, coupon)| check.is_valid() && coupon.free_shipping);
+        let mut total = validator.subtotal();
+        if !settings.ignore_discounts {
+            total -= applied.discount_total;
+        }
+        let min_amount = settings
+            .min_amount
+            .trim()
+            .parse::<f64>()
+            .unwrap_or_default();
+        let has_min_amount = total + 1e-9 >= min_amount;
+        match &settings.requires {
+            FreeShippingRequires::Nothing => Ok(true),
+            FreeShippingRequires::Coupon => Ok(has_coupon),
+            FreeShippingRequires::MinAmount => Ok(has_min_amount),
+            FreeShippingRequires::Either => Ok(has_coupon || has_min_amount),
+            FreeShippingRequires::Both => Ok(has_coupon && has_min_amount),
+            FreeShippingRequires::Other(requires) => {
+                Err(anyhow!("Unsupported free shipping requirement {requires}"))
+            }
+        }
+    }
+    /// Order with the items, coupons, fees, shipping and addresses of the cart.
+    /// Fails if the cart has [`problems`](Cart::problems) or no billing email.
+    pub fn to_order(&self) -> Result<CreateOrder> {
+        let problems = self.problems();
+        if !problems.is_empty() {
+            return Err(anyhow!(
+                "Cart can't be ordered: {}",
+                problems
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ));
+        }
+        let mut order = Order::create();
+        for (product, variation, quantity) in self.lines() {
+            let mut line = OrderLineItemCreate::new()
+                .product_id(product.id)
+                .quantity(quantity);
+            if let Some(variation) = variation {
+                line = line.variation_id(variation.id);
+            }
+            order = order.line_item(line);
+        }
+        for code in &self.coupons {
+            order = order.coupon_line(code);
+        }
+        for fee in &self.fees {
+            order = order.fee_line(fee.to_owned());
+        }
+        if let Some(CartShipping {
+            method: Some(method),
+            ..
+        }) = &self.shipping
+        {
+            let total = format!("{:.*}", self.decimals as usize, self.shipping_total()?);
+            order = order.shipping_line(ShippingLineCreate::new(
+                &method.title,
+                &method.method_id,
+                total,
+            ));
+        }
+        if let Some(customer_id) = self.customer_id {
+            order = order.customer_id(customer_id);
+        }
+        if let Some(billing) = &self.billing {
+            order = order.billing(billing.to_owned());
+        }
+        if let Some(shipping) = &self.shipping_address {
+            order = order.shipping(shipping.to_owned());
+        }
+        if let Some(note) = &self.customer_note {
+            order = order.customer_note(note);
+        }
+        order.build()
+    }
+    /// Loaded items with the quantities of the same product or variation added up.
+    fn lines(&self) -> Vec<(&Product, Option<&ProductVariation>, i32)> {
+        let mut lines: Vec<(&Product, Option<&ProductVariation>, i32)> = vec![];
+        for item in &self.items {
+            let CartItem::Loaded {
+                product,
+                variation,
+                quantity,
+            } = item
+            else {
+                continue;
+            };
+            let variation = variation.as_deref();
+            match lines
+                .iter_mut()
+                .find(|(p, v, _)| p.id == product.id && v.map(|v| v.id) == variation.map(|v| v.id))
+            {
+                Some(line) => line.2 += quantity,
+                None => lines.push((product, variation, *quantity)),
+            }
+        }
+        lines
+    }
+}
+/// Purchasable and stock checks of a line, like WooCommerce does when adding to the cart.
+/// The quantity is the one taken from the stock of the line, for variations using the stock
+/// of the product the quantity of all of them.
+fn stock_problem(
+    product: &Product,
+    variation: Option<&ProductVariation>,
+    quantity: i32,
+) -> Option<CartProblem> {
+    let (id, stock_id, purchasable, stock_status, managed, stock, backorders_allowed) =
+        match variation {
+            Some(variation) => {
+                let (stock_id, managed, stock, backorders_allowed) = match variation.manage_stock {
+                    ManageStock::Bool(managed) => (
+                        variation.id,
+                        managed,
+                        variation.stock_quantity,
+                        variation.backorders_allowed,
+                    ),
+                    ManageStock::Parent(_) => (
+                        product.id,
+                        product.manage_stock,
+                        product.stock_quantity,
+                        product.backorders_allowed,
+                    ),
+                };
+                (
+                    variation.id,
+                    stock_id,
+                    variation.purchasable,
+                    &variation.stock_status,
+                    managed,
+                    stock,
+                    backorders_allowed,
+                )
+            }
+            None if matches!(product.product_type, ProductType::Variable) => {
+                return Some(CartProblem::VariationRequired(product.id))
+            }
+            None => (
+                product.id,
+                product.id,
+                product.purchasable,
+                &product.stock_status,
+                product.manage_stock,
+                product.stock_quantity,
+                product.backorders_allowed,
+            ),
+        };
+    if !purchasable {
+        return Some(CartProblem::NotPurchasable(id));
+    }
+    if matches!(stock_status, StockStatus::Outofstock) {
+        return Some(CartProblem::OutOfStock(id));
+    }
+    match stock {
+        Some(available) if managed && !backorders_allowed && available < quantity => {
+            Some(CartProblem::InsufficientStock {
+                id: stock_id,
+                requested: quantity,
+                available,
+            })
+        }
+        _ => None,
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ShippingZone;
+
+    fn product(id: i32, product_type: &str, stock: i32) -> Product {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "type": product_type,
+            "price": "20",
+            "purchasable": true,
+            "stock_status": "instock",
+            "manage_stock": true,
+            "stock_quantity": stock,
+        }))
+        .unwrap()
+    }
+    fn variation(id: i32) -> ProductVariation {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "price": "20",
+            "purchasable": true,
+            "stock_status": "instock",
+            "manage_stock": "parent",
+        }))
+        .unwrap()
+    }
+    fn zone(method_id: &str, settings: serde_json::Value) -> ResolvedShippingZone {
+        let settings = settings
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(id, value)| (id.clone(), serde_json::json!({ "id": id, "value": value })))
+            .collect::<serde_json::Map<_, _>>();
+        ResolvedShippingZone {
+            zone: ShippingZone::default(),
+            methods: vec![serde_json::from_value(serde_json::json!({
+                "instance_id": 1,
+                "title": method_id,
+                "enabled": true,
+                "method_id": method_id,
+                "settings": settings,
+            }))
+            .unwrap()],
+        }
+    }
+    fn coupon(code: &str, discount_type: &str, amount: &str, free_shipping: bool) -> Coupon {
+        serde_json::from_value(serde_json::json!({
+            "code": code,
+            "discount_type": discount_type,
+            "amount": amount,
+            "free_shipping": free_shipping,
+        }))
+        .unwrap()
+    }
+    #[test]
+    fn variations_share_the_stock_of_the_product() {
+        let parent = product(2, "variable", 3);
+        let cart =
+            Cart::new()
+                .variation(&parent, &variation(21), 2)
+                .variation(&parent, &variation(22), 2);
+        assert_eq!(
+            cart.problems(),
+            [CartProblem::InsufficientStock {
+                id: 2,
+                requested: 4,
+                available: 3
+            }]
+        );
+        let cart =
+            Cart::new()
+                .variation(&parent, &variation(21), 2)
+                .variation(&parent, &variation(22), 1);
+        assert!(cart.problems().is_empty());
+    }
+    #[test]
+    fn simple_product_stock() {
+        let cart = Cart::new().product(&product(1, "simple", 5), 6);
+        assert_eq!(
+            cart.problems(),
+            [CartProblem::InsufficientStock {
+                id: 1,
+                requested: 6,
+                available: 5
+            }]
+        );
+        let cart = Cart::new().product(&product(2, "variable", 5), 1);
+        assert_eq!(cart.problems(), [CartProblem::VariationRequired(2)]);
+    }
+    #[test]
+    fn flat_rate_and_cost_override() {
+        let zone = zone("flat_rate", serde_json::json!({ "cost": "5 + [qty]" }));
+        let cart = Cart::new()
+            .product(&product(1, "simple", 10), 3)
+            .shipping(&zone, 1);
+        assert_eq!(cart.shipping_total().unwrap(), 8.0);
+        let cart = Cart::new()
+            .shipping_cost(2.5)
+            .product(&product(1, "simple", 10), 3)
+            .shipping(&zone, 1);
+        assert_eq!(cart.shipping_total().unwrap(), 2.5);
+    }
+    #[test]
+    fn unsupported_methods_need_a_cost() {
+        let zone = zone("table_rate", serde_json::json!({}));
+        let cart = Cart::new()
+            .product(&product(1, "simple", 10), 1)
+            .shipping(&zone, 1);
+        assert!(cart.shipping_total().is_err());
+        assert_eq!(cart.shipping_cost(7.5).shipping_total().unwrap(), 7.5);
+    }
+    #[test]
+    fn free_shipping_minimum_amount() {
+        let zone = zone(
+            "free_shipping",
+            serde_json::json!({ "requires": "min_amount", "min_amount": "50" }),
+        );
+        let cart = |quantity| {
+            Cart::new()
+                .product(&product(1, "simple", 10), quantity)
+                .shipping(&zone, 1)
+        };
+        assert!(cart(2).shipping_total().is_err());
+        assert_eq!(cart(3).shipping_total().unwrap(), 0.0);
+        let discounted = cart(3).loaded_coupon(&coupon("ten", "percent", "20", false));
+        assert!(discounted.shipping_total().is_err());
+        assert!(cart(3).coupon("unknown").shipping_total().is_err());
+        let zone = self::zone(
+            "free_shipping",
+            serde_json::json!({
+                "requires": "min_amount",
+                "min_amount": "50",
+                "ignore_discounts": "yes",
+            }),
+        );
+        let cart = Cart::new()
+            .product(&product(1, "simple", 10), 3)
+            .loaded_coupon(&coupon("ten", "percent", "20", false))
+            .shipping(&zone, 1);
+        assert_eq!(cart.shipping_total().unwrap(), 0.0);
+    }
+    #[test]
+    fn free_shipping_coupon() {
+        let zone = zone(
+            "free_shipping",
+            serde_json::json!({ "requires": "either", "min_amount": "100" }),
+        );
+        let cart = Cart::new()
+            .product(&product(1, "simple", 10), 1)
+            .shipping(&zone, 1);
+        assert!(cart.shipping_total().is_err());
+        let cart = cart.loaded_coupon(&coupon("ship", "fixed_cart", "0", true));
+        assert_eq!(cart.shipping_total().unwrap(), 0.0);
+    }
+}
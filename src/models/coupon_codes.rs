@@ -0,0 +1,247 @@
+use rand::Rng;
+
+use super::coupons::Coupon;
+
+/// Pattern of generated coupon codes, see
+/// [`ApiClient::generate_coupons`](crate::ApiClient::generate_coupons).
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_woocommerce::{CodeCharset, CodePattern};
+///
+/// let pattern = CodePattern::new()
+///     .prefix("SUMMER-")
+///     .charset(CodeCharset::Readable)
+///     .length(8)
+///     .checksum();
+/// let code = pattern.generate();
+/// assert!(pattern.is_valid(&code));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CodePattern {
+    prefix: String,
+    suffix: String,
+    charset: CodeCharset,
+    length: usize,
+    checksum: bool,
+}
+/// Characters of the random part of generated codes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CodeCharset {
+    /// Digits only.
+    Numeric,
+    /// Uppercase letters only.
+    Letters,
+    /// Uppercase letters and digits.
+    Alphanumeric,
+    /// Uppercase letters and digits without the ones easily confused, e.g. `0`, `O`, `1` and `I`.
+    #[default]
+    Readable,
+    /// Any other characters.
+    Custom(String),
+}
+impl CodeCharset {
+    fn characters(&self) -> Vec<char> {
+        let characters = match self {
+            CodeCharset::Numeric => "0123456789",
+            CodeCharset::Letters => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            CodeCharset::Alphanumeric => "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+            CodeCharset::Readable => "ABCDEFGHJKLMNPQRSTUVWXYZ23456789",
+            CodeCharset::Custom(characters) => characters,
+        };
+        let mut unique = vec![];
+        for c in characters.chars() {
+            if !unique.contains(&c) {
+                unique.push(c);
+            }
+        }
+        unique
+    }
+}
+impl Default for CodePattern {
+    fn default() -> Self {
+        CodePattern {
+            prefix: String::new(),
+            suffix: String::new(),
+            charset: CodeCharset::default(),
+            length: 8,
+            checksum: false,
+        }
+    }
+}
+impl CodePattern {
+    pub fn new() -> Self {
+        CodePattern::default()
+    }
+    /// Text before the random part, e.g. `SUMMER-`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+    /// Text after the random part.
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+    /// Characters of the random part. Default is [`CodeCharset::Readable`].
+    pub fn charset(mut self, charset: CodeCharset) -> Self {
+        self.charset = charset;
+        self
+    }
+    /// Number of random characters. Default is 8.
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+    /// Add a check character after the random part (Luhn mod N over the charset),
+    /// so mistyped codes can be told apart from unknown ones.
+    pub fn checksum(mut self) -> Self {
+        self.checksum = true;
+        self
+    }
+    /// Number of different codes the pattern can produce, `None` if it doesn't fit in `u64`.
+    pub fn capacity(&self) -> Option<u64> {
+        let base = self.charset.characters().len() as u64;
+        u32::try_from(self.length)
+            .ok()
+            .and_then(|length| base.checked_pow(length))
+    }
+    /// True if the charset has no characters, so no random part can be generated.
+    pub fn is_empty(&self) -> bool {
+        self.charset.characters().is_empty()
+    }
+    /// New random code.
+    pub fn generate(&self) -> String {
+        let characters = self.charset.characters();
+        if characters.is_empty() {
+            return format!("{}{}", self.prefix, self.suffix);
+        }
+        let mut rng = rand::thread_rng();
+        let random = (0..self.length)
+            .map(|_| characters[rng.gen_range(0..characters.len())])
+            .collect::<String>();
+        let check = match self.checksum {
+            true => check_character(&random, &characters).map(String::from),
+            false => None,
+        };
+        format!(
+            "{}{random}{}{}",
+            self.prefix,
+            check.unwrap_or_default(),
+            self.suffix
+        )
+    }
+    /// True if the code has the prefix, suffix, length, characters and check character of the pattern.
+    /// Case is ignored, as WooCommerce saves coupon codes in lowercase.
+    pub fn is_valid(&self, code: &str) -> bool {
+        let characters = self
+            .charset
+            .characters()
+            .iter()
+            .flat_map(|c| c.to_lowercase())
+            .collect::<Vec<_>>();
+        if characters.is_empty() {
+            return false;
+        }
+        let code = code.to_lowercase();
+        let Some(body) = code
+            .strip_prefix(self.prefix.to_lowercase().as_str())
+            .and_then(|rest| rest.strip_suffix(self.suffix.to_lowercase().as_str()))
+        else {
+            return false;
+        };
+        let mut body = body.chars().collect::<Vec<_>>();
+        let check = match self.checksum {
+            true => body.pop(),
+            false => None,
+        };
+        let random = body.iter().collect::<String>();
+        body.len() == self.length
+            && body.iter().all(|c| characters.contains(c))
+            && check == check_character(&random, &characters).filter(|_| self.checksum)
+    }
+}
+/// Luhn mod N check character of the code.
+fn check_character(code: &str, characters: &[char]) -> Option<char> {
+    let n = characters.len();
+    if n == 0 {
+        return None;
+    }
+    let mut sum = 0;
+    for (i, c) in code.chars().rev().enumerate() {
+        let mut addend = characters.iter().position(|x| *x == c)?;
+        if i % 2 == 0 {
+            addend *= 2;
+            addend = addend / n + addend % n;
+        }
+        sum += addend;
+    }
+    characters.get((n - sum % n) % n).copied()
+}
+/// Coupons created by [`ApiClient::generate_coupons`](crate::ApiClient::generate_coupons).
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedCoupons {
+    /// Coupons that were created.
+    pub created: Vec<Coupon>,
+    /// Codes no coupon was created for, as their batch item or whole batch request failed.
+    pub failed: Vec<String>,
+}
+impl GeneratedCoupons {
+    /// Created coupons as CSV with `id` and `code` columns.
+    pub fn csv(&self) -> String {
+        let mut csv = String::from("id,code\n");
+        for coupon in &self.created {
+            csv.push_str(&format!("{},{}\n", coupon.id, csv_field(&coupon.code)));
+        }
+        csv
+    }
+}
+/// Quote a CSV field if needed.
+fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_catches_typos() {
+        let pattern = CodePattern::new()
+            .prefix("SUMMER-")
+            .charset(CodeCharset::Numeric)
+            .length(6)
+            .checksum();
+        let code = pattern.generate();
+        assert_eq!(code.len(), 14);
+        assert!(pattern.is_valid(&code.to_lowercase()));
+        let mut typo = code.into_bytes();
+        typo[8] = if typo[8] == b'9' { b'0' } else { typo[8] + 1 };
+        assert!(!pattern.is_valid(&String::from_utf8(typo).unwrap()));
+    }
+    #[test]
+    fn empty_charset() {
+        let pattern = CodePattern::new()
+            .charset(CodeCharset::Custom(String::new()))
+            .checksum();
+        assert!(pattern.is_empty());
+        assert!(!pattern.is_valid("ABC"));
+        assert!(!pattern.is_valid(""));
+    }
+    #[test]
+    fn csv_quotes_fields() {
+        let coupon = |id: i32, code: &str| Coupon {
+            id,
+            code: code.to_string(),
+            ..Default::default()
+        };
+        let generated = GeneratedCoupons {
+            created: vec![coupon(1, "plain"), coupon(2, "a,\"b\"")],
+            failed: vec![],
+        };
+        assert_eq!(generated.csv(), "id,code\n1,plain\n2,\"a,\"\"b\"\"\"\n");
+    }
+}
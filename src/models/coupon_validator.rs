@@ -0,0 +1,547 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{
+    coupons::{Coupon, DiscountType},
+    customers::Customer,
+    order_totals::{coupon_applies, round, CalculatorItem, OrderCalculator},
+    product_variations::ProductVariation,
+    products::Product,
+    store_settings::StoreSettings,
+};
+
+/// Offline check of coupons against a cart, the way WooCommerce validates and applies them.
+///
+/// # Example
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use rust_woocommerce::{ApiClient, Config, Coupon, CouponValidator, Product};
+/// use tracing::info;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     tracing_subscriber::fmt::init();
+///     let config = Config::new("woo.toml")?;
+///     let client = ApiClient::new(&config)?;
+///     let product = client.retrieve::<Product>(3982).await?;
+///     let first = client.retrieve::<Coupon>(719).await?;
+///     let second = client.retrieve::<Coupon>(720).await?;
+///     let result = CouponValidator::new()
+///         .email("someone@example.com")
+///         .product(&product, 2)
+///         .apply(&[first, second]);
+///     for check in &result.coupons {
+///         match check.is_valid() {
+///             true => info!("{}: -{}", check.code, check.discount),
+///             false => info!("{}: {}", check.code, check.errors[0]),
+///         }
+///     }
+///     info!("Total discount {}", result.discount_total);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CouponValidator {
+    items: Vec<CalculatorItem>,
+    email: String,
+    customer_id: Option<i32>,
+    now: Option<DateTime<Utc>>,
+    settings: Option<StoreSettings>,
+}
+/// Reason a coupon can't be used, with the message WooCommerce shows.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CouponError {
+    /// The coupon expiry date has passed.
+    Expired,
+    /// The coupon has been used as often as allowed.
+    UsageLimitReached,
+    /// The customer has used the coupon as often as allowed.
+    UsageLimitPerUserReached,
+    /// The cart subtotal is below the minimum spend.
+    MinimumSpend(f64),
+    /// The cart subtotal is above the maximum spend.
+    MaximumSpend(f64),
+    /// The coupon is restricted to other emails, or no email is given.
+    EmailRestricted,
+    /// None of the cart items is one of the coupon products.
+    ProductsRequired,
+    /// None of the cart items is in one of the coupon categories.
+    CategoriesRequired,
+    /// No cart item can be discounted by the product coupon.
+    NoEligibleItems,
+    /// Cart coupon with excluded products in the cart, by ID.
+    ExcludedProducts(Vec<i32>),
+    /// Cart coupon with products of excluded categories in the cart, by category ID.
+    ExcludedCategories(Vec<i32>),
+    /// Cart coupon that excludes sale items, with sale items in the cart.
+    SaleItems,
+    /// Another coupon with the same code is applied.
+    AlreadyApplied,
+    /// An individual use coupon is applied, by code.
+    IndividualUse(String),
+    /// Removed by an individual use coupon applied later, by code.
+    RemovedByIndividualUse(String),
+    /// Discount type not known to WooCommerce core.
+    UnsupportedDiscountType(String),
+}
+impl Display for CouponError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids = |ids: &[i32]| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            CouponError::Expired => write!(f, "This coupon has expired."),
+            CouponError::UsageLimitReached => write!(f, "Coupon usage limit has been reached."),
+            CouponError::UsageLimitPerUserReached => {
+                write!(f, "Coupon usage limit has been reached for this customer.")
+            }
+            CouponError::MinimumSpend(amount) => {
+                write!(f, "The minimum spend for this coupon is {amount}.")
+            }
+            CouponError::MaximumSpend(amount) => {
+                write!(f, "The maximum spend for this coupon is {amount}.")
+            }
+            CouponError::EmailRestricted => write!(f, "Sorry, it seems this coupon is not yours."),
+            CouponError::ProductsRequired
+            | CouponError::CategoriesRequired
+            | CouponError::NoEligibleItems => write!(
+                f,
+                "Sorry, this coupon is not applicable to selected products."
+            ),
+            CouponError::ExcludedProducts(products) => write!(
+                f,
+                "Sorry, this coupon is not applicable to the products: {}.",
+                ids(products)
+            ),
+            CouponError::ExcludedCategories(categories) => write!(
+                f,
+                "Sorry, this coupon is not applicable to the categories: {}.",
+                ids(categories)
+            ),
+            CouponError::SaleItems => write!(f, "Sorry, this coupon is not valid for sale items."),
+            CouponError::AlreadyApplied => write!(f, "Coupon code already applied!"),
+            CouponError::IndividualUse(code) => write!(
+                f,
+                "Sorry, coupon \"{code}\" has already been applied and cannot be used in conjunction with other coupons."
+            ),
+            CouponError::RemovedByIndividualUse(code) => write!(
+                f,
+                "Removed because coupon \"{code}\" cannot be used in conjunction with other coupons."
+            ),
+            CouponError::UnsupportedDiscountType(discount_type) => {
+                write!(f, "Unsupported discount type {discount_type}.")
+            }
+        }
+    }
+}
+impl std::error::Error for CouponError {}
+/// Coupons applied to a cart, see [`CouponValidator::apply`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CouponDiscounts {
+    /// Result of every coupon, in the order they were given.
+    pub coupons: Vec<CouponCheck>,
+    /// Discount of every cart item by all valid coupons, in the order the items were added.
+    pub line_discounts: Vec<f64>,
+    /// Total discount of all valid coupons.
+    pub discount_total: f64,
+}
+/// Result of one coupon.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CouponCheck {
+    /// Coupon code.
+    pub code: String,
+    /// Reasons the coupon isn't applied, empty if it is.
+    pub errors: Vec<CouponError>,
+    /// Discount of every cart item by this coupon.
+    pub line_discounts: Vec<f64>,
+    /// Total discount by this coupon.
+    pub discount: f64,
+}
+impl CouponCheck {
+    /// True if the coupon is applied.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+impl CouponValidator {
+    pub fn new() -> Self {
+        CouponValidator::default()
+    }
+    /// Product at its current price.
+    pub fn product(self, product: &Product, quantity: i32) -> Self {
+        self.line(CalculatorItem::product(product, quantity))
+    }
+    /// Variation at its current price. The parent product gives the categories.
+    pub fn variation(self, product: &Product, variation: &ProductVariation, quantity: i32) -> Self {
+        self.line(CalculatorItem::variation(product, variation, quantity))
+    }
+    /// Cart item with all its details.
+    pub fn line(mut self, item: CalculatorItem) -> Self {
+        self.items.push(item);
+        self
+    }
+    /// Billing email of the customer, checked against email restrictions and usage per user.
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = email.into();
+        self
+    }
+    /// Registered customer, gives the email and ID checked against usage per user.
+    pub fn customer(mut self, customer: &Customer) -> Self {
+        let _ = self.customer_id.insert(customer.id);
+        self.email(&customer.email)
+    }
+    /// Time expiry dates are checked against. Default is now.
+    pub fn now(mut self, now: DateTime<Utc>) -> Self {
+        let _ = self.now.insert(now);
+        self
+    }
+    /// Rounding and discount settings of the store.
+    pub fn settings(mut self, settings: &StoreSettings) -> Self {
+        let _ = self.settings.insert(settings.to_owned());
+        self
+    }
+    /// Sum of the cart items, including tax if prices are entered with tax.
+    pub fn subtotal(&self) -> f64 {
+        self.items
+            .iter()
+            .map(|item| item.price * item.quantity as f64)
+            .sum()
+    }
+    /// Reasons a coupon can't be used with the cart on its own, empty if it can.
+    pub fn validate(&self, coupon: &Coupon) -> Vec<CouponError> {
+        let mut errors = vec![];
+        let now = self.now.unwrap_or_else(Utc::now);
+        if coupon
+            .date_expires_utc()
+            .is_some_and(|expires| now > expires)
+        {
+            errors.push(CouponError::Expired);
+        }
+        if coupon
+            .usage_limit
+            .is_some_and(|limit| limit > 0 && coupon.usage_count >= limit)
+        {
+            errors.push(CouponError::UsageLimitReached);
+        }
+        if coupon
+            .usage_limit_per_user
+            .is_some_and(|limit| limit > 0 && self.usage_count(coupon) >= limit as usize)
+        {
+            errors.push(CouponError::UsageLimitPerUserReached);
+        }
+        let subtotal = self.subtotal();
+        let minimum = amount(&coupon.minimum_amount);
+        if minimum > 0.0 && subtotal < minimum {
+            errors.push(CouponError::MinimumSpend(minimum));
+        }
+        let maximum = amount(&coupon.maximum_amount);
+        if maximum > 0.0 && subtotal > maximum {
+            errors.push(CouponError::MaximumSpend(maximum));
+        }
+        if !coupon.email_restrictions.is_empty()
+            && !coupon
+                .email_restrictions
+                .iter()
+                .any(|pattern| email_matches(pattern, &self.email))
+        {
+            errors.push(CouponError::EmailRestricted);
+        }
+        if !coupon.product_ids.is_empty()
+            && !self.items.iter().any(|item| {
+                coupon.product_ids.contains(&item.product_id)
+                    || item
+                        .variation_id
+                        .is_some_and(|id| coupon.product_ids.contains(&id))
+            })
+        {
+            errors.push(CouponError::ProductsRequired);
+        }
+        if !coupon.product_categories.is_empty()
+            && !self.items.iter().any(|item| {
+                item.category_ids
+                    .iter()
+                    .any(|c| coupon.product_categories.contains(c))
+            })
+        {
+            errors.push(CouponError::CategoriesRequired);
+        }
+        match &coupon.discount_type {
+            DiscountType::Percent | DiscountType::FixedProduct => {
+                if errors.iter().all(|e| {
+                    !matches!(
+                        e,
+                        CouponError::ProductsRequired | CouponError::CategoriesRequired
+                    )
+                }) && !self.items.iter().any(|item| coupon_applies(coupon, item))
+                {
+                    errors.push(CouponError::NoEligibleItems);
+                }
+            }
+            DiscountType::FixedCart => errors.extend(self.excluded_items(coupon)),
+            DiscountType::Other(discount_type) => errors.push(
+                CouponError::UnsupportedDiscountType(discount_type.to_owned()),
+            ),
+        }
+        errors
+    }
+    /// Apply coupons in the given order, like entering them one after another in the cart.
+    /// Invalid coupons are skipped, an individual use coupon removes the coupons before it
+    /// and blocks the ones after it. Discounts are on the prices as entered.
+    pub fn apply(&self, coupons: &[Coupon]) -> CouponDiscounts {
+        let mut checks: Vec<CouponCheck> = vec![];
+        let mut applied: Vec<usize> = vec![];
+        for (i, coupon) in coupons.iter().enumerate() {
+            let mut errors = self.validate(coupon);
+            if applied
+                .iter()
+                .any(|a| coupons[*a].code.eq_ignore_ascii_case(&coupon.code))
+            {
+                errors.push(CouponError::AlreadyApplied);
+            } else if let Some(a) = applied.iter().find(|a| coupons[**a].individual_use) {
+                errors.push(CouponError::IndividualUse(coupons[*a].code.to_owned()));
+            }
+            if errors.is_empty() && coupon.individual_use {
+                for a in applied.drain(..) {
+                    checks[a]
+                        .errors
+                        .push(CouponError::RemovedByIndividualUse(coupon.code.to_owned()));
+                }
+            }
+            if errors.is_empty() {
+                applied.push(i);
+            }
+            checks.push(CouponCheck {
+                code: coupon.code.to_owned(),
+                errors,
+                line_discounts: vec![0.0; self.items.len()],
+                discount: 0.0,
+            });
+        }
+        let mut calculator = OrderCalculator::new();
+        if let Some(settings) = &self.settings {
+            calculator = calculator.settings(settings);
+        }
+        for item in &self.items {
+            calculator = calculator.line(item.to_owned());
+        }
+        for a in &applied {
+            calculator = calculator.coupon(&coupons[*a]);
+        }
+        let decimals = self.settings.as_ref().map_or(2, |s| s.price_decimals);
+        let mut line_discounts = vec![0.0; self.items.len()];
+        for (a, discounts) in applied.iter().zip(calculator.discounts_by_coupon()) {
+            for (total, discount) in line_discounts.iter_mut().zip(&discounts) {
+                *total = round(*total + discount, decimals);
+            }
+            let check = &mut checks[*a];
+            check.discount = round(discounts.iter().sum(), decimals);
+            check.line_discounts = discounts;
+        }
+        CouponDiscounts {
+            coupons: checks,
+            discount_total: round(line_discounts.iter().sum(), decimals),
+            line_discounts,
+        }
+    }
+    /// Times the customer used the coupon, by email or customer ID.
+    fn usage_count(&self, coupon: &Coupon) -> usize {
+        let customer_id = self.customer_id.map(|id| id.to_string());
+        coupon
+            .used_by
+            .iter()
+            .filter(|used_by| {
+                (!self.email.is_empty() && used_by.eq_ignore_ascii_case(self.email.trim()))
+                    || customer_id.as_ref() == Some(used_by)
+            })
+            .count()
+    }
+    /// Cart coupons can't be used if any cart item is excluded.
+    fn excluded_items(&self, coupon: &Coupon) -> Vec<CouponError> {
+        let mut errors = vec![];
+        let mut products = vec![];
+        let mut categories = vec![];
+        for item in &self.items {
+            for id in [item.product_id, item.variation_id.unwrap_or_default()] {
+                if id != 0 && coupon.excluded_product_ids.contains(&id) && !products.contains(&id) {
+                    products.push(id);
+                }
+            }
+            for id in &item.category_ids {
+                if coupon.excluded_product_categories.contains(id) && !categories.contains(id) {
+                    categories.push(*id);
+                }
+            }
+        }
+        if !products.is_empty() {
+            errors.push(CouponError::ExcludedProducts(products));
+        }
+        if !categories.is_empty() {
+            errors.push(CouponError::ExcludedCategories(categories));
+        }
+        if coupon.exclude_sale_items && self.items.iter().any(|item| item.on_sale) {
+            errors.push(CouponError::SaleItems);
+        }
+        errors
+    }
+}
+/// Email restrictions can contain `*` wildcards matching any characters, none included,
+/// e.g. `*@example.com`.
+fn email_matches(pattern: &str, email: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let email = email.trim().to_lowercase();
+    if email.is_empty() {
+        return false;
+    }
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = email.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+fn amount(value: &str) -> f64 {
+    value.trim().parse().unwrap_or_default()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coupon(value: serde_json::Value) -> Coupon {
+        serde_json::from_value(value).unwrap()
+    }
+    fn item(product_id: i32, quantity: i32, price: f64) -> CalculatorItem {
+        CalculatorItem {
+            product_id,
+            quantity,
+            price,
+            taxable: true,
+            ..Default::default()
+        }
+    }
+    #[test]
+    fn email_wildcards() {
+        assert!(email_matches("*@example.com", "someone@example.com"));
+        assert!(email_matches("*@example.com", "@example.com"));
+        assert!(email_matches("some*@example.com", "some@example.com"));
+        assert!(email_matches("s*e*@example.com", "se@example.com"));
+        assert!(email_matches("*", "someone@example.com"));
+        assert!(email_matches(" Someone@Example.com", "someone@example.com"));
+        assert!(!email_matches("*@example.com", "someone@example.org"));
+        assert!(!email_matches(
+            "someone@example.com",
+            "someone@example.com.au"
+        ));
+        assert!(!email_matches("*@example.com", ""));
+    }
+    #[test]
+    fn restrictions() {
+        let validator = CouponValidator::new()
+            .now("2024-06-01T00:00:00Z".parse().unwrap())
+            .email("someone@example.com")
+            .line(item(1, 2, 10.0));
+        let coupon = coupon(serde_json::json!({
+            "code": "limited",
+            "discount_type": "percent",
+            "amount": "10",
+            "date_expires_gmt": "2024-05-31T23:59:59",
+            "usage_limit": 5,
+            "usage_count": 5,
+            "usage_limit_per_user": 1,
+            "used_by": ["SOMEONE@example.com"],
+            "minimum_amount": "25.00",
+            "email_restrictions": ["*@example.org"],
+            "product_ids": [2],
+        }));
+        assert_eq!(
+            validator.validate(&coupon),
+            [
+                CouponError::Expired,
+                CouponError::UsageLimitReached,
+                CouponError::UsageLimitPerUserReached,
+                CouponError::MinimumSpend(25.0),
+                CouponError::EmailRestricted,
+                CouponError::ProductsRequired,
+            ]
+        );
+    }
+    #[test]
+    fn cart_coupons_exclude_items() {
+        let mut sale = item(2, 1, 5.0);
+        sale.on_sale = true;
+        let validator = CouponValidator::new().line(item(1, 1, 10.0)).line(sale);
+        let coupon = coupon(serde_json::json!({
+            "code": "cart",
+            "discount_type": "fixed_cart",
+            "amount": "5",
+            "excluded_product_ids": [2, 3],
+            "exclude_sale_items": true,
+        }));
+        assert_eq!(
+            validator.validate(&coupon),
+            [
+                CouponError::ExcludedProducts(vec![2]),
+                CouponError::SaleItems
+            ]
+        );
+    }
+    #[test]
+    fn individual_use_and_duplicates() {
+        let percent = coupon(serde_json::json!({
+            "code": "ten", "discount_type": "percent", "amount": "10",
+        }));
+        let individual = coupon(serde_json::json!({
+            "code": "solo", "discount_type": "fixed_cart", "amount": "3", "individual_use": true,
+        }));
+        let result = CouponValidator::new()
+            .line(item(1, 1, 20.0))
+            .line(item(2, 1, 10.0))
+            .apply(&[percent.clone(), individual, percent]);
+        assert_eq!(
+            result.coupons[0].errors,
+            [CouponError::RemovedByIndividualUse(String::from("solo"))]
+        );
+        assert!(result.coupons[1].is_valid());
+        assert_eq!(
+            result.coupons[2].errors,
+            [CouponError::IndividualUse(String::from("solo"))]
+        );
+        assert_eq!(result.coupons[1].line_discounts, [2.0, 1.0]);
+        assert_eq!(result.discount_total, 3.0);
+    }
+    #[test]
+    fn applies_valid_coupons_only() {
+        let percent = coupon(serde_json::json!({
+            "code": "ten", "discount_type": "percent", "amount": "10",
+        }));
+        let expensive = coupon(serde_json::json!({
+            "code": "big", "discount_type": "fixed_cart", "amount": "5", "minimum_amount": "100",
+        }));
+        let result = CouponValidator::new().line(item(1, 3, 10.0)).apply(&[
+            expensive,
+            percent.clone(),
+            percent,
+        ]);
+        assert_eq!(result.coupons[0].errors, [CouponError::MinimumSpend(100.0)]);
+        assert_eq!(result.coupons[1].discount, 3.0);
+        assert_eq!(result.coupons[2].errors, [CouponError::AlreadyApplied]);
+        assert_eq!(result.line_discounts, [3.0]);
+        assert_eq!(result.discount_total, 3.0);
+    }
+}
@@ -1,4 +1,5 @@
-use chrono::NaiveDateTime;
+use super::lenient;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::coupons::{
@@ -6,64 +7,94 @@ use crate::controllers::coupons::{
 };
 use crate::controllers::Entity;
 
-use super::MetaData;
+use super::MetaBag;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Coupon {
     /// Unique identifier for the object.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Coupon code.
+    #[serde(deserialize_with = "lenient::string")]
     pub code: String,
     /// The amount of discount. Should always be numeric, even if setting a percentage.
+    #[serde(deserialize_with = "lenient::string")]
     pub amount: String,
     /// The date the coupon was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the coupon was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// The date the coupon was last modified, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified: NaiveDateTime,
     /// The date the coupon was last modified, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified_gmt: NaiveDateTime,
     /// Determines the type of discount that will be applied. Options: percent, fixed_cart and fixed_product. Default is fixed_cart.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub discount_type: DiscountType,
     /// Coupon description.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// The date the coupon expires, in the site's timezone.
-    pub date_expires: Option<String>,
+    #[serde(deserialize_with = "lenient::option")]
+    pub date_expires: Option<NaiveDateTime>,
     /// The date the coupon expires, as GMT.
-    pub date_expires_gmt: Option<String>,
+    #[serde(deserialize_with = "lenient::option")]
+    pub date_expires_gmt: Option<NaiveDateTime>,
     /// Number of times the coupon has been used already.
+    #[serde(deserialize_with = "lenient::number")]
     pub usage_count: i32,
     /// If true, the coupon can only be used individually. Other applied coupons will be removed from the cart. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub individual_use: bool,
     /// List of product IDs the coupon can be used on.
+    #[serde(deserialize_with = "lenient::vec")]
     pub product_ids: Vec<i32>,
     /// List of product IDs the coupon cannot be used on.
+    #[serde(deserialize_with = "lenient::vec")]
     pub excluded_product_ids: Vec<i32>,
     /// How many times the coupon can be used in total.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub usage_limit: Option<i32>,
     /// How many times the coupon can be used per customer.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub usage_limit_per_user: Option<i32>,
     /// Max number of items in the cart the coupon can be applied to.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub limit_usage_to_x_items: Option<i32>,
     /// If true and if the free shipping method requires a coupon, this coupon will enable free shipping. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub free_shipping: bool,
     /// List of category IDs the coupon applies to.
+    #[serde(deserialize_with = "lenient::vec")]
     pub product_categories: Vec<i32>,
     /// List of category IDs the coupon does not apply to.
+    #[serde(deserialize_with = "lenient::vec")]
     pub excluded_product_categories: Vec<i32>,
     /// If true, this coupon will not be applied to items that have sale prices. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub exclude_sale_items: bool,
     /// Minimum order amount that needs to be in the cart before coupon applies.
+    #[serde(deserialize_with = "lenient::string")]
     pub minimum_amount: String,
     /// Maximum order amount allowed when using the coupon.
+    #[serde(deserialize_with = "lenient::string")]
     pub maximum_amount: String,
     /// List of email addresses that can use this coupon.
+    #[serde(deserialize_with = "lenient::vec")]
     pub email_restrictions: Vec<String>,
     /// List of user IDs (or guest email addresses) that have used the coupon.
+    #[serde(deserialize_with = "lenient::vec")]
     pub used_by: Vec<String>,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for Coupon {
     fn endpoint() -> String {
@@ -81,6 +112,18 @@ impl Coupon {
     pub fn update() -> UpdateCouponBuilder {
         UpdateCouponBuilder::default()
     }
+    /// The date the coupon was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
+    /// The date the coupon was last modified, as UTC.
+    pub fn date_modified_utc(&self) -> DateTime<Utc> {
+        self.date_modified_gmt.and_utc()
+    }
+    /// The date the coupon expires, as UTC.
+    pub fn date_expires_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_expires_gmt.map(|d| d.and_utc())
+    }
 }
 /// Determines the type of discount that will be applied.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -90,4 +133,7 @@ pub enum DiscountType {
     FixedCart,
     FixedProduct,
     Percent,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
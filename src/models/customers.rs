@@ -1,40 +1,61 @@
-use chrono::NaiveDateTime;
+use super::lenient;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::customers::{CreateCustomer, UpdateCustomer};
 use crate::controllers::Entity;
 
-use super::MetaData;
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::MetaBag;
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Customer {
     /// Unique identifier for the resource
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// The date the customer was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the customer was created, as GMT
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub date_created_gmt: NaiveDateTime,
+    /// The date the customer was last modified, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified: NaiveDateTime,
     /// The date the customer was last modified, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified_gmt: NaiveDateTime,
     /// The email address for the customer
+    #[serde(deserialize_with = "lenient::string")]
     pub email: String,
     /// Customer first name.    
+    #[serde(deserialize_with = "lenient::string")]
     pub first_name: String,
     /// Customer last name.
+    #[serde(deserialize_with = "lenient::string")]
     pub last_name: String,
     /// Customer role.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub role: Role,
     /// Customer login name.
+    #[serde(deserialize_with = "lenient::string")]
     pub username: String,
     /// List of billing address data.    
+    #[serde(deserialize_with = "lenient::or_default")]
     pub billing: Billing,
     /// List of shipping address data.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub shipping: Shipping,
     /// Is the customer a paying customer
+    #[serde(deserialize_with = "lenient::bool")]
     pub is_paying_customer: bool,
     /// Avatar URL
+    #[serde(deserialize_with = "lenient::string")]
     pub avatar_url: String,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for Customer {
     fn endpoint() -> String {
@@ -46,50 +67,78 @@ impl Entity for Customer {
     }
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Billing {
     /// First name.
+    #[serde(deserialize_with = "lenient::string")]
     pub first_name: String,
     /// Last name.
+    #[serde(deserialize_with = "lenient::string")]
     pub last_name: String,
     /// Company name.
+    #[serde(deserialize_with = "lenient::string")]
     pub company: String,
     /// Address line 1
+    #[serde(deserialize_with = "lenient::string")]
     pub address_1: String,
     /// Address line 2
+    #[serde(deserialize_with = "lenient::string")]
     pub address_2: String,
     /// City name.
+    #[serde(deserialize_with = "lenient::string")]
     pub city: String,
     /// ISO code or name of the state, province or district.
+    #[serde(deserialize_with = "lenient::string")]
     pub state: String,
     /// Postal code.
+    #[serde(deserialize_with = "lenient::string")]
     pub postcode: String,
     /// ISO code of the country.
+    #[serde(deserialize_with = "lenient::string")]
     pub country: String,
     /// Email address.
+    #[serde(deserialize_with = "lenient::string")]
     pub email: String,
     /// Phone number.
+    #[serde(deserialize_with = "lenient::string")]
     pub phone: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Shipping {
     /// First name.
+    #[serde(deserialize_with = "lenient::string")]
     pub first_name: String,
     /// Last name.
+    #[serde(deserialize_with = "lenient::string")]
     pub last_name: String,
     /// Company name.
+    #[serde(deserialize_with = "lenient::string")]
     pub company: String,
     /// Address line 1
+    #[serde(deserialize_with = "lenient::string")]
     pub address_1: String,
     /// Address line 2
+    #[serde(deserialize_with = "lenient::string")]
     pub address_2: String,
     /// City name.
+    #[serde(deserialize_with = "lenient::string")]
     pub city: String,
     /// ISO code or name of the state, province or district.
+    #[serde(deserialize_with = "lenient::string")]
     pub state: String,
     /// Postal code.
+    #[serde(deserialize_with = "lenient::string")]
     pub postcode: String,
     /// ISO code of the country.
+    #[serde(deserialize_with = "lenient::string")]
     pub country: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -103,6 +152,9 @@ pub enum Role {
     #[default]
     Customer,
     ShopManager,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 impl Customer {
     pub fn create<T: ToString>(email: T) -> CreateCustomer {
@@ -111,4 +163,92 @@ impl Customer {
     pub fn update() -> UpdateCustomer {
         UpdateCustomer::default()
     }
+    /// The date the customer was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
+    /// The date the customer was last modified, as UTC.
+    pub fn date_modified_utc(&self) -> DateTime<Utc> {
+        self.date_modified_gmt.and_utc()
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CustomerDownload {
+    /// Download ID.
+    #[serde(deserialize_with = "lenient::string")]
+    pub download_id: String,
+    /// Download file URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub download_url: String,
+    /// Downloadable product ID.
+    #[serde(deserialize_with = "lenient::number")]
+    pub product_id: i32,
+    /// Product name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub product_name: String,
+    /// Downloadable file name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub download_name: String,
+    /// Order ID.
+    #[serde(deserialize_with = "lenient::number")]
+    pub order_id: i32,
+    /// Order key.
+    #[serde(deserialize_with = "lenient::string")]
+    pub order_key: String,
+    /// Number of downloads remaining, `unlimited` if there is no limit.
+    #[serde(deserialize_with = "lenient::string")]
+    pub downloads_remaining: String,
+    /// The date when download access expires, in the site's timezone, `never` if it doesn't.
+    #[serde(deserialize_with = "lenient::string")]
+    pub access_expires: String,
+    /// The date when download access expires, as GMT, `never` if it doesn't.
+    #[serde(deserialize_with = "lenient::string")]
+    pub access_expires_gmt: String,
+    /// File details.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub file: CustomerDownloadFile,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl Entity for CustomerDownload {
+    fn endpoint() -> String {
+        String::new()
+    }
+    fn child_endpoint(parent_id: i32) -> String {
+        format!("customers/{parent_id}/downloads/")
+    }
+}
+impl CustomerDownload {
+    /// Number of downloads remaining, None if there is no limit.
+    pub fn remaining(&self) -> Option<i32> {
+        self.downloads_remaining.parse().ok()
+    }
+    /// The date when download access expires, None if it never does.
+    pub fn access_expires_utc(&self) -> Option<DateTime<Utc>> {
+        NaiveDateTime::parse_from_str(&self.access_expires_gmt, "%Y-%m-%dT%H:%M:%S")
+            .ok()
+            .map(|d| d.and_utc())
+    }
+    /// True if the customer still can download the file at the given moment.
+    pub fn is_available(&self, now: DateTime<Utc>) -> bool {
+        self.remaining().map_or(true, |r| r > 0)
+            && self
+                .access_expires_utc()
+                .map_or(true, |expires| expires > now)
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CustomerDownloadFile {
+    /// File name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub name: String,
+    /// File URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub file: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
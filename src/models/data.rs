@@ -1,12 +1,21 @@
+use std::collections::HashMap;
+
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Data {
     /// Data resource ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
     /// Data resource description.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Entity for Data {
@@ -18,53 +27,122 @@ impl Entity for Data {
         String::new()
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Continent {
     /// 2 character continent code
+    #[serde(deserialize_with = "lenient::string")]
     pub code: String,
     /// Full name of continent.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// List of countries on this continent.
+    #[serde(deserialize_with = "lenient::vec")]
     pub countries: Vec<Country>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl Entity for Continent {
+    fn endpoint() -> String {
+        String::from("data/continents/")
+    }
+    fn child_endpoint(parent_id: i32) -> String {
+        let _ = parent_id;
+        String::new()
+    }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Country {
     /// ISO3166 alpha-2 country code    
+    #[serde(deserialize_with = "lenient::string")]
     pub code: String,
     /// Default ISO4127 alpha-3 currency code for the country.
+    #[serde(deserialize_with = "lenient::option")]
     pub currency_code: Option<String>,
     /// Currency symbol position for this country.
+    #[serde(deserialize_with = "lenient::option")]
     pub currency_pos: Option<String>,
     /// Decimal separator for displayed prices for this country.
+    #[serde(deserialize_with = "lenient::option")]
     pub decimal_sep: Option<String>,
     /// The unit lengths are defined in for this country.
+    #[serde(deserialize_with = "lenient::option")]
     pub dimension_unit: Option<String>,
     /// Full name of country.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Number of decimal points shown in displayed prices for this country.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub num_decimals: Option<i32>,
     /// List of states in this country. See Continents - Countries - States properties    
+    #[serde(deserialize_with = "lenient::vec")]
     pub states: Vec<State>,
     /// Thousands separator for displayed prices in this country.
+    #[serde(deserialize_with = "lenient::option")]
     pub thousand_sep: Option<String>,
     /// The unit weights are defined in for this country.
+    #[serde(deserialize_with = "lenient::option")]
     pub weight_unit: Option<String>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl Entity for Country {
+    fn endpoint() -> String {
+        String::from("data/countries/")
+    }
+    fn child_endpoint(parent_id: i32) -> String {
+        let _ = parent_id;
+        String::new()
+    }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct State {
     /// State code.
     pub code: serde_json::Value,
     /// Full name of state.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl State {
+    /// State code as a string, some stores send numeric codes.
+    pub fn code_str(&self) -> String {
+        match &self.code {
+            serde_json::Value::String(code) => code.clone(),
+            code => code.to_string(),
+        }
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Currency {
     /// ISO4217 currency code.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub code: CurrencyISO,
     /// Full name of currency.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Currency symbol.
+    #[serde(deserialize_with = "lenient::string")]
     pub symbol: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl Entity for Currency {
+    fn endpoint() -> String {
+        String::from("data/currencies/")
+    }
+    fn child_endpoint(parent_id: i32) -> String {
+        let _ = parent_id;
+        String::new()
+    }
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Default)]
 pub enum CurrencyISO {
@@ -234,4 +312,255 @@ pub enum CurrencyISO {
     YER,
     ZAR,
     ZMW,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+/// In-memory index of the store's continents, countries, states and currencies.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "GeoData", into = "GeoData")]
+pub struct Geo {
+    continents: Vec<Continent>,
+    currencies: Vec<Currency>,
+    /// Continent and country positions by uppercase country code.
+    country_index: HashMap<String, (usize, usize)>,
+    /// State positions by uppercase country and state code.
+    state_index: HashMap<(String, String), usize>,
+    /// Currency positions by uppercase currency code.
+    currency_index: HashMap<String, usize>,
+}
+/// Serialized form of [`Geo`], the indexes are rebuilt when it is read.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct GeoData {
+    continents: Vec<Continent>,
+    currencies: Vec<Currency>,
+}
+impl From<GeoData> for Geo {
+    fn from(value: GeoData) -> Self {
+        Geo::new(value.continents, value.currencies)
+    }
+}
+impl From<Geo> for GeoData {
+    fn from(value: Geo) -> Self {
+        GeoData {
+            continents: value.continents,
+            currencies: value.currencies,
+        }
+    }
+}
+impl Geo {
+    /// Build the index from the `data/continents` and `data/currencies` responses.
+    pub fn new(continents: Vec<Continent>, currencies: Vec<Currency>) -> Self {
+        let mut country_index = HashMap::new();
+        let mut state_index = HashMap::new();
+        for (i, continent) in continents.iter().enumerate() {
+            for (j, country) in continent.countries.iter().enumerate() {
+                let country_code = country.code.to_uppercase();
+                for (k, state) in country.states.iter().enumerate() {
+                    let key = (country_code.clone(), state.code_str().to_uppercase());
+                    state_index.entry(key).or_insert(k);
+                }
+                country_index.entry(country_code).or_insert((i, j));
+            }
+        }
+        let mut currency_index = HashMap::new();
+        for (i, currency) in currencies.iter().enumerate() {
+            currency_index
+                .entry(currency_key(&currency.code))
+                .or_insert(i);
+        }
+        Geo {
+            continents,
+            currencies,
+            country_index,
+            state_index,
+            currency_index,
+        }
+    }
+    /// All countries.
+    pub fn countries(&self) -> impl Iterator<Item = &Country> {
+        self.continents.iter().flat_map(|c| c.countries.iter())
+    }
+    /// Country by ISO3166 alpha-2 code, case insensitive.
+    pub fn country(&self, code: &str) -> Option<&Country> {
+        let (i, j) = self.country_index.get(&code.to_uppercase())?;
+        Some(&self.continents[*i].countries[*j])
+    }
+    /// Continent the country is on.
+    pub fn continent_of(&self, country_code: &str) -> Option<&Continent> {
+        let (i, _) = self.country_index.get(&country_code.to_uppercase())?;
+        Some(&self.continents[*i])
+    }
+    /// States of the country, empty for countries without states.
+    pub fn states(&self, country_code: &str) -> &[State] {
+        self.country(country_code)
+            .map(|c| c.states.as_slice())
+            .unwrap_or_default()
+    }
+    /// State by country and state code, case insensitive.
+    pub fn state(&self, country_code: &str, state_code: &str) -> Option<&State> {
+        let country = self.country(country_code)?;
+        self.state_in(country, state_code)
+    }
+    /// True if the state code is valid for the country. Any state is valid
+    /// for countries without a list of states.
+    pub fn is_valid_state(&self, country_code: &str, state_code: &str) -> bool {
+        match self.country(country_code) {
+            Some(country) if !country.states.is_empty() => {
+                self.state_in(country, state_code).is_some()
+            }
+            _ => true,
+        }
+    }
+    /// Currency by ISO4217 code.
+    pub fn currency(&self, code: &CurrencyISO) -> Option<&Currency> {
+        self.currency_by_code(&currency_key(code))
+    }
+    /// Default currency and price format of the country.
+    /// The symbol is decoded from the HTML entities WooCommerce sends, e.g. `&#36;` as `$`.
+    pub fn currency_format(&self, country_code: &str) -> Option<CurrencyFormat> {
+        let country = self.country(country_code)?;
+        let code = country.currency_code.clone()?;
+        let symbol = self
+            .currency_by_code(&code)
+            .map(|c| decode_html_entities(&c.symbol))
+            .unwrap_or_default();
+        Some(CurrencyFormat {
+            code,
+            symbol,
+            position: country.currency_pos.clone().unwrap_or_default(),
+            decimal_separator: country.decimal_sep.clone().unwrap_or_default(),
+            thousand_separator: country.thousand_sep.clone().unwrap_or_default(),
+            decimals: country.num_decimals.unwrap_or(2),
+        })
+    }
+    fn state_in<'a>(&self, country: &'a Country, state_code: &str) -> Option<&'a State> {
+        let key = (country.code.to_uppercase(), state_code.to_uppercase());
+        self.state_index.get(&key).map(|k| &country.states[*k])
+    }
+    fn currency_by_code(&self, code: &str) -> Option<&Currency> {
+        let i = self.currency_index.get(&code.to_uppercase())?;
+        Some(&self.currencies[*i])
+    }
+}
+/// Currency code as sent by WooCommerce, uppercase.
+fn currency_key(code: &CurrencyISO) -> String {
+    match serde_json::json!(code) {
+        serde_json::Value::String(code) => code.to_uppercase(),
+        code => code.to_string(),
+    }
+}
+/// Currency and price format of a country.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyFormat {
+    /// ISO4217 currency code.
+    pub code: String,
+    /// Currency symbol.
+    pub symbol: String,
+    /// Currency symbol position, e.g. `left` or `right_space`.
+    pub position: String,
+    /// Decimal separator.
+    pub decimal_separator: String,
+    /// Thousand separator.
+    pub thousand_separator: String,
+    /// Number of decimals.
+    pub decimals: i32,
+}
+/// WooCommerce sends currency symbols as HTML entities, e.g. `&#36;` or `&euro;`.
+pub(crate) fn decode_html_entities(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "euro" => Some('€'),
+            "pound" => Some('£'),
+            "yen" => Some('¥'),
+            "cent" => Some('¢'),
+            _ => entity.strip_prefix('#').and_then(|code| {
+                match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse().ok(),
+                }
+                .and_then(char::from_u32)
+            }),
+        };
+        match decoded {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geo() -> Geo {
+        let continents = serde_json::from_value(serde_json::json!([{
+            "code": "NA",
+            "name": "North America",
+            "countries": [
+                {
+                    "code": "US",
+                    "name": "United States",
+                    "currency_code": "USD",
+                    "currency_pos": "left",
+                    "decimal_sep": ".",
+                    "thousand_sep": ",",
+                    "num_decimals": 2,
+                    "states": [{"code": "CA", "name": "California"}, {"code": "NY", "name": "New York"}]
+                },
+                {"code": "BM", "name": "Bermuda", "states": []}
+            ]
+        }]))
+        .unwrap();
+        let currencies = serde_json::from_value(serde_json::json!([
+            {"code": "USD", "name": "United States dollar", "symbol": "&#36;"},
+            {"code": "EUR", "name": "Euro", "symbol": "&euro;"}
+        ]))
+        .unwrap();
+        Geo::new(continents, currencies)
+    }
+    #[test]
+    fn lookups_are_case_insensitive() {
+        let geo = geo();
+        assert_eq!(geo.country("us").unwrap().name, "United States");
+        assert_eq!(geo.continent_of("bm").unwrap().code, "NA");
+        assert_eq!(geo.state("US", "ny").unwrap().name, "New York");
+        assert!(geo.is_valid_state("US", "ca"));
+        assert!(!geo.is_valid_state("US", "TX"));
+        assert!(geo.is_valid_state("BM", "anything"));
+        assert!(geo.country("FR").is_none());
+        assert_eq!(geo.currency(&CurrencyISO::EUR).unwrap().name, "Euro");
+    }
+    #[test]
+    fn currency_format_decodes_symbol() {
+        let format = geo().currency_format("US").unwrap();
+        assert_eq!(format.code, "USD");
+        assert_eq!(format.symbol, "$");
+        assert_eq!(
+            decode_html_entities("&euro; &amp; &#x20AC; &bogus;"),
+            "€ & € &bogus;"
+        );
+    }
+    #[test]
+    fn indexes_are_rebuilt_after_deserializing() {
+        let json = serde_json::to_string(&geo()).unwrap();
+        let geo: Geo = serde_json::from_str(&json).unwrap();
+        assert_eq!(geo.state("US", "CA").unwrap().name, "California");
+    }
 }
@@ -0,0 +1,346 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+
+use super::{
+    product_variations::ProductVariation,
+    products::Product,
+    shipping_zone_methods::{
+        FlatRateCalculation, FlatRateSettings, ShippingMethodConfig, ShippingZoneMethod,
+    },
+};
+
+/// Contents of a cart to quote flat rate shipping for, the way WooCommerce computes it.
+///
+/// # Example
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use rust_woocommerce::{ApiClient, Config, FlatRatePackage, Product, ShippingZoneMethod};
+/// use tracing::info;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     tracing_subscriber::fmt::init();
+///     let config = Config::new("woo.toml")?;
+///     let client = ApiClient::new(&config)?;
+///     let product = client.retrieve::<Product>(3982).await?;
+///     let package = FlatRatePackage::new().product(&product, 2).item(1, 12.5, 0);
+///     let methods = client.list_all_subentities::<ShippingZoneMethod>(1).await?;
+///     for method in methods.iter().filter(|m| m.method_id == "flat_rate") {
+///         if let Some(cost) = method.flat_rate_cost(&package)? {
+///             info!("{}: {cost}", method.title);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FlatRatePackage {
+    items: Vec<FlatRateItem>,
+}
+/// Item of a package that needs shipping.
+#[derive(Debug, Clone, Default)]
+pub struct FlatRateItem {
+    /// Quantity in the cart.
+    pub quantity: i32,
+    /// Line total (after discounts), used for `[cost]` and `[fee]`.
+    pub cost: f64,
+    /// Shipping class ID, 0 for products without a shipping class.
+    pub shipping_class_id: i32,
+}
+impl FlatRatePackage {
+    pub fn new() -> Self {
+        FlatRatePackage::default()
+    }
+    /// Item with its quantity, line total and shipping class ID (0 for no class).
+    pub fn item(mut self, quantity: i32, cost: f64, shipping_class_id: i32) -> Self {
+        self.items.push(FlatRateItem {
+            quantity,
+            cost,
+            shipping_class_id,
+        });
+        self
+    }
+    /// Product at its current price.
+    pub fn product(self, product: &Product, quantity: i32) -> Self {
+        let cost = amount(&product.price) * quantity as f64;
+        self.item(quantity, cost, product.shipping_class_id)
+    }
+    /// Variation at its current price. Variations without a shipping class use the one of the product.
+    pub fn variation(self, product: &Product, variation: &ProductVariation, quantity: i32) -> Self {
+        let cost = amount(&variation.price) * quantity as f64;
+        let shipping_class_id = match variation.shipping_class_id {
+            0 => product.shipping_class_id,
+            id => id,
+        };
+        self.item(quantity, cost, shipping_class_id)
+    }
+    /// Total quantity of the package.
+    pub fn quantity(&self) -> i32 {
+        self.items.iter().map(|i| i.quantity).sum()
+    }
+    /// Total cost of the package.
+    pub fn cost(&self) -> f64 {
+        self.items.iter().map(|i| i.cost).sum()
+    }
+    /// Quantity and cost by shipping class ID.
+    fn classes(&self) -> BTreeMap<i32, (i32, f64)> {
+        let mut classes = BTreeMap::new();
+        for item in &self.items {
+            let (quantity, cost) = classes.entry(item.shipping_class_id).or_insert((0, 0.0));
+            *quantity += item.quantity;
+            *cost += item.cost;
+        }
+        classes
+    }
+}
+impl ShippingZoneMethod {
+    /// Cost of a `flat_rate` method for the package, `None` if the method has no costs set.
+    /// Fails for other methods and for invalid formulas.
+    pub fn flat_rate_cost(&self, package: &FlatRatePackage) -> Result<Option<f64>> {
+        match self.typed_settings() {
+            ShippingMethodConfig::FlatRate(settings) => settings.package_cost(package),
+            _ => Err(anyhow!(
+                "Shipping method {} is {}, not flat_rate",
+                self.instance_id,
+                self.method_id
+            )),
+        }
+    }
+}
+impl FlatRateSettings {
+    /// Cost of the package, `None` if neither the method nor the classes of the package have costs set.
+    /// Class costs are only used when the store has shipping classes, like WooCommerce does.
+    pub fn package_cost(&self, package: &FlatRatePackage) -> Result<Option<f64>> {
+        let mut has_costs = false;
+        let mut cost = 0.0;
+        if !self.cost.trim().is_empty() {
+            has_costs = true;
+            cost = FlatRateSettings::evaluate_cost(&self.cost, package.quantity(), package.cost())?;
+        }
+        if !self.class_costs.is_empty() {
+            let mut highest_class_cost = 0_f64;
+            for (class_id, (quantity, class_total)) in package.classes() {
+                let formula = match class_id {
+                    0 => &self.no_class_cost,
+                    id => match self.class_costs.get(&id) {
+                        Some(formula) => formula,
+                        None => continue,
+                    },
+                };
+                if formula.trim().is_empty() {
+                    continue;
+                }
+                has_costs = true;
+                let class_cost = FlatRateSettings::evaluate_cost(formula, quantity, class_total)?;
+                match self.calculation_type {
+                    FlatRateCalculation::Order => {
+                        highest_class_cost = highest_class_cost.max(class_cost)
+                    }
+                    _ => cost += class_cost,
+                }
+            }
+            cost += highest_class_cost;
+        }
+        Ok(has_costs.then_some(cost))
+    }
+    /// Evaluate a cost formula, e.g. `10 + [qty] * 2 + [fee percent="10" min_fee="5"]`.
+    /// `[qty]` is the number of items, `[cost]` their total cost and `[fee]` a percentage
+    /// of the cost with optional `min_fee` and `max_fee`. An empty formula costs 0.
+    pub fn evaluate_cost(formula: &str, quantity: i32, cost: f64) -> Result<f64> {
+        let replaced = formula
+            .replace("[qty]", &quantity.to_string())
+            .replace("[cost]", &cost.to_string());
+        let expanded = expand_fees(&replaced, cost)
+            .ok_or_else(|| anyhow!("Invalid fee in cost formula {formula:?}"))?;
+        let sum = expanded
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == ',' { '.' } else { c })
+            .collect::<String>();
+        let sum = sum
+            .trim_start_matches(['+', '*', '/'])
+            .trim_end_matches(['+', '-', '*', '/']);
+        if sum.is_empty() {
+            return Ok(0.0);
+        }
+        Expression::new(sum)
+            .evaluate()
+            .ok_or_else(|| anyhow!("Invalid cost formula {formula:?}"))
+    }
+}
+/// Replace `[fee]` shortcodes with their value.
+fn expand_fees(formula: &str, cost: f64) -> Option<String> {
+    let mut expanded = String::new();
+    let mut rest = formula;
+    while let Some(start) = rest.find("[fee") {
+        let end = start + rest[start..].find(']')?;
+        expanded.push_str(&rest[..start]);
+        let attributes = shortcode_attributes(&rest[start + 4..end])?;
+        let attribute = |name: &str| {
+            attributes
+                .get(name)
+                .map(|v| v.trim().parse::<f64>().unwrap_or_default())
+                .filter(|v| *v != 0.0)
+        };
+        let mut fee = attribute("percent").map_or(0.0, |percent| cost * percent / 100.0);
+        if let Some(min_fee) = attribute("min_fee").filter(|min| fee < *min) {
+            fee = min_fee;
+        }
+        if let Some(max_fee) = attribute("max_fee").filter(|max| fee > *max) {
+            fee = max_fee;
+        }
+        expanded.push_str(&fee.to_string());
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Some(expanded)
+}
+/// Attributes of a shortcode, e.g. `percent="10" min_fee='5' max_fee=20`.
+fn shortcode_attributes(text: &str) -> Option<BTreeMap<String, String>> {
+    let mut attributes = BTreeMap::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let (name, value) = rest.split_once('=')?;
+        let value = value.trim_start();
+        let (value, remaining) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split_once(quote)?,
+            _ => value.split_once(char::is_whitespace).unwrap_or((value, "")),
+        };
+        attributes.insert(name.trim().to_lowercase(), value.to_string());
+        rest = remaining.trim_start();
+    }
+    Some(attributes)
+}
+/// Arithmetic with `+ - * / ^` and parentheses, like WooCommerce `WC_Eval_Math`.
+struct Expression<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+impl<'a> Expression<'a> {
+    fn new(text: &'a str) -> Self {
+        Expression {
+            chars: text.chars().peekable(),
+        }
+    }
+    fn evaluate(mut self) -> Option<f64> {
+        let value = self.sum()?;
+        match self.chars.next() {
+            None if value.is_finite() => Some(value),
+            _ => None,
+        }
+    }
+    fn sum(&mut self) -> Option<f64> {
+        let mut value = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.chars.peek().copied() {
+            self.chars.next();
+            let rhs = self.product()?;
+            value = match op {
+                '+' => value + rhs,
+                _ => value - rhs,
+            };
+        }
+        Some(value)
+    }
+    fn product(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.chars.peek().copied() {
+            self.chars.next();
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value * rhs,
+                _ if rhs == 0.0 => return None,
+                _ => value / rhs,
+            };
+        }
+        Some(value)
+    }
+    /// Negation binds looser than powers, `-2^2` is -4.
+    fn unary(&mut self) -> Option<f64> {
+        match self.chars.peek() {
+            Some('-') => {
+                self.chars.next();
+                Some(-self.unary()?)
+            }
+            Some('+') => {
+                self.chars.next();
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+    fn power(&mut self) -> Option<f64> {
+        let base = self.operand()?;
+        match self.chars.next_if_eq(&'^') {
+            Some(_) => Some(base.powf(self.unary()?)),
+            None => Some(base),
+        }
+    }
+    fn operand(&mut self) -> Option<f64> {
+        if self.chars.next_if_eq(&'(').is_some() {
+            let value = self.sum()?;
+            return self.chars.next_if_eq(&')').map(|_| value);
+        }
+        let mut number = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            number.push(c);
+        }
+        number.parse().ok()
+    }
+}
+fn amount(value: &str) -> f64 {
+    value.trim().parse().unwrap_or_default()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(formula: &str, quantity: i32, cost: f64) -> f64 {
+        FlatRateSettings::evaluate_cost(formula, quantity, cost).unwrap()
+    }
+    #[test]
+    fn formulas() {
+        assert_eq!(evaluate("10 + [qty] * 2", 3, 0.0), 16.0);
+        assert_eq!(evaluate("[cost] * 0.1", 1, 50.0), 5.0);
+        assert_eq!(evaluate("(1 + 2) * 3", 0, 0.0), 9.0);
+        assert_eq!(evaluate("2^3", 0, 0.0), 8.0);
+        assert_eq!(evaluate("-2^2", 0, 0.0), -4.0);
+        assert_eq!(evaluate("5,5", 0, 0.0), 5.5);
+        assert_eq!(evaluate("10 +", 0, 0.0), 10.0);
+        assert_eq!(evaluate(" ", 0, 0.0), 0.0);
+        assert!(FlatRateSettings::evaluate_cost("10 / 0", 0, 0.0).is_err());
+        assert!(FlatRateSettings::evaluate_cost("10 + abc", 0, 0.0).is_err());
+        assert!(FlatRateSettings::evaluate_cost("(1 + 2", 0, 0.0).is_err());
+    }
+    #[test]
+    fn fees() {
+        let fee = r#"[fee percent="10" min_fee="5" max_fee='8']"#;
+        assert_eq!(evaluate(fee, 1, 20.0), 5.0);
+        assert_eq!(evaluate(fee, 1, 70.0), 7.0);
+        assert_eq!(evaluate(fee, 1, 100.0), 8.0);
+        assert_eq!(evaluate("2 + [fee percent=50]", 1, 10.0), 7.0);
+        assert_eq!(evaluate("[fee]", 1, 10.0), 0.0);
+        assert!(FlatRateSettings::evaluate_cost("[fee percent=10", 1, 10.0).is_err());
+    }
+    #[test]
+    fn class_costs() {
+        let mut settings = FlatRateSettings {
+            cost: String::from("5"),
+            class_costs: BTreeMap::from([(1, String::from("10")), (2, String::from("[qty] * 3"))]),
+            no_class_cost: String::from("1"),
+            ..Default::default()
+        };
+        let package = FlatRatePackage::new()
+            .item(1, 10.0, 1)
+            .item(2, 20.0, 2)
+            .item(1, 5.0, 0)
+            .item(1, 5.0, 3);
+        assert_eq!(settings.package_cost(&package).unwrap(), Some(22.0));
+        settings.calculation_type = FlatRateCalculation::Order;
+        assert_eq!(settings.package_cost(&package).unwrap(), Some(15.0));
+        assert_eq!(
+            FlatRateSettings::default().package_cost(&package).unwrap(),
+            None
+        );
+    }
+}
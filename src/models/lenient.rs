@@ -0,0 +1,135 @@
+//! Tolerant deserializers for values that stores with plugins tend to mangle:
+//! `""` or `null` instead of numbers, `false` instead of objects, numbers instead of strings.
+use std::str::FromStr;
+
+use serde::{de::DeserializeOwned, de::Error, Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Numbers, numeric strings, `""` and `null` (as the default value).
+pub(crate) fn number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + FromStr + Default,
+{
+    option_number(deserializer).map(Option::unwrap_or_default)
+}
+/// Numbers and numeric strings, with `""`, `null` and `false` as None.
+pub(crate) fn option_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + FromStr,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null | Value::Bool(false) => Ok(None),
+        Value::String(s) if s.trim().is_empty() => Ok(None),
+        Value::String(s) => s
+            .trim()
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| D::Error::custom(format!("invalid number: {s}"))),
+        value => T::deserialize(value).map(Some).map_err(D::Error::custom),
+    }
+}
+/// Strings, with numbers and booleans converted and `null` as an empty string.
+pub(crate) fn string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null | Value::Bool(false) => Ok(String::new()),
+        Value::Bool(true) => Ok(String::from("1")),
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        value => Err(D::Error::custom(format!("expected a string, got {value}"))),
+    }
+}
+/// Booleans, with `"yes"`/`"no"`, `"1"`/`"0"`, numbers and `null` accepted.
+pub(crate) fn bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Bool(b) => Ok(b),
+        Value::Null => Ok(false),
+        Value::Number(n) => Ok(n.as_f64().unwrap_or_default() != 0.0),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "" | "0" | "no" | "false" | "off" => Ok(false),
+            "1" | "yes" | "true" | "on" => Ok(true),
+            _ => Err(D::Error::custom(format!("invalid boolean: {s}"))),
+        },
+        value => Err(D::Error::custom(format!("expected a boolean, got {value}"))),
+    }
+}
+/// Any value, with `null`, `false`, `""` and `[]` as None. Handles objects sent as `false`.
+pub(crate) fn option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null | Value::Bool(false) => Ok(None),
+        Value::String(s) if s.trim().is_empty() => Ok(None),
+        Value::Array(a) if a.is_empty() => Ok(None),
+        value => T::deserialize(value).map(Some).map_err(D::Error::custom),
+    }
+}
+/// Any value, with `null`, `false`, `""` and `[]` as the default value.
+pub(crate) fn or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    option(deserializer).map(Option::unwrap_or_default)
+}
+/// Lists, with `null`, `false` and `""` as an empty list and objects (PHP arrays with keys)
+/// read as the list of their values, numeric keys in numeric order.
+pub(crate) fn vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null | Value::Bool(false) => Ok(Vec::new()),
+        Value::String(s) if s.trim().is_empty() => Ok(Vec::new()),
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by_cached_key(|(key, _)| match key.parse::<i64>() {
+                Ok(index) => (0, index, String::new()),
+                Err(_) => (1, 0, key.to_owned()),
+            });
+            entries
+                .into_iter()
+                .map(|(_, v)| T::deserialize(v).map_err(D::Error::custom))
+                .collect()
+        }
+        value => Vec::<T>::deserialize(value).map_err(D::Error::custom),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct List {
+        #[serde(deserialize_with = "super::vec")]
+        items: Vec<String>,
+    }
+
+    #[test]
+    fn vec_from_php_array_with_keys() {
+        let json = r#"{"items": {"10": "c", "2": "b", "key": "d", "0": "a"}}"#;
+        let list: List = serde_json::from_str(json).unwrap();
+        assert_eq!(list.items, ["a", "b", "c", "d"]);
+    }
+    #[test]
+    fn vec_from_empty_values() {
+        for json in [
+            r#"{"items": null}"#,
+            r#"{"items": false}"#,
+            r#"{"items": ""}"#,
+        ] {
+            let list: List = serde_json::from_str(json).unwrap();
+            assert!(list.items.is_empty());
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Postcode matching shared by tax rates and shipping zones.
+
+/// Postcodes are compared in upper case, without spaces and dashes.
+pub(crate) fn normalize_postcode(postcode: &str) -> String {
+    postcode
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+/// True if the postcode matches the pattern: an exact postcode,
+/// a prefix with a `*` wildcard, e.g. `CB*`, or a range, e.g. `12345...12399` or `AB10...AB20`.
+/// Ranges compare the postcodes with their letters turned into numbers, like WooCommerce.
+pub(crate) fn postcode_matches(pattern: &str, postcode: &str) -> bool {
+    let postcode = normalize_postcode(postcode);
+    if let Some((min, max)) = pattern.split_once("...") {
+        let encoded = numeric_postcode(&postcode);
+        let bound = |value: &str| {
+            let mut bound = numeric_postcode(&normalize_postcode(value));
+            while bound.len() < encoded.len() {
+                bound.push('0');
+            }
+            bound
+        };
+        let (min, max) = (bound(min), bound(max));
+        return !postcode.is_empty()
+            && compare_numbers(&min, &encoded).is_le()
+            && compare_numbers(&encoded, &max).is_le();
+    }
+    let pattern = normalize_postcode(pattern);
+    match pattern.strip_suffix('*') {
+        Some(prefix) => postcode.starts_with(prefix),
+        None => pattern == postcode,
+    }
+}
+/// WooCommerce `wc_make_numeric_postcode`: every character as two digits,
+/// digits as themselves and letters by their position in the alphabet, e.g. `AB1` is `010201`.
+fn numeric_postcode(postcode: &str) -> String {
+    postcode
+        .chars()
+        .map(|c| match c {
+            '0'..='9' => c as u32 - '0' as u32,
+            'A'..='Z' => c as u32 - 'A' as u32 + 1,
+            _ => 0,
+        })
+        .map(|n| format!("{n:02}"))
+        .collect()
+}
+/// Compare strings of digits as numbers, whatever their length.
+fn compare_numbers(a: &str, b: &str) -> std::cmp::Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_ranges() {
+        assert!(postcode_matches("12345...12399", "12350"));
+        assert!(postcode_matches("12345 ... 12399", "12399"));
+        assert!(!postcode_matches("12345...12399", "12400"));
+        assert!(!postcode_matches("12345...12399", "1235"));
+        assert!(!postcode_matches("12345...12399", ""));
+    }
+    #[test]
+    fn alphanumeric_ranges() {
+        assert_eq!(numeric_postcode("AB1"), "010201");
+        assert!(postcode_matches("AB10...AB20", "ab15"));
+        assert!(postcode_matches("AB10...AB20", "AB20"));
+        assert!(!postcode_matches("AB10...AB20", "AB21"));
+        assert!(!postcode_matches("AB10...AB20", "AC10"));
+        assert!(postcode_matches("AB1...AB2", "AB1 2CD"));
+        assert!(!postcode_matches("AB1...AB2", "AB3 2CD"));
+    }
+    #[test]
+    fn wildcards_and_exact_postcodes() {
+        assert!(postcode_matches("CB*", "cb2 1tn"));
+        assert!(!postcode_matches("CB*", "C2"));
+        assert!(postcode_matches("SW1A 1AA", "sw1a-1aa"));
+        assert!(!postcode_matches("SW1A 1AA", "SW1A 1AB"));
+    }
+}
@@ -0,0 +1,32 @@
+use super::lenient;
+use serde::{Deserialize, Serialize};
+
+/// WordPress media item, e.g. an uploaded product image.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Media {
+    /// Attachment ID, used as image ID of products and variations.
+    #[serde(deserialize_with = "lenient::number")]
+    pub id: i32,
+    /// Attachment slug.
+    #[serde(deserialize_with = "lenient::string")]
+    pub slug: String,
+    /// URL of the attachment page.
+    #[serde(deserialize_with = "lenient::string")]
+    pub link: String,
+    /// URL of the original file.
+    #[serde(deserialize_with = "lenient::string")]
+    pub source_url: String,
+    /// Alternative text.
+    #[serde(deserialize_with = "lenient::string")]
+    pub alt_text: String,
+    /// MIME type, e.g. `image/jpeg`.
+    #[serde(deserialize_with = "lenient::string")]
+    pub mime_type: String,
+    /// Media type, `image` or `file`.
+    #[serde(deserialize_with = "lenient::string")]
+    pub media_type: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
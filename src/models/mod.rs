@@ -1,9 +1,19 @@
 use serde_with::skip_serializing_none;
 
+mod lenient;
+mod location;
+
+pub mod cart;
+pub mod coupon_codes;
+pub mod coupon_validator;
 pub mod coupons;
 pub mod customers;
 pub mod data;
+pub mod flat_rate;
+pub mod media;
 pub mod order_notes;
+pub mod order_totals;
+pub mod order_workflow;
 pub mod orders;
 pub mod payment_gateways;
 pub mod product_attribute_terms;
@@ -20,8 +30,12 @@ pub mod settings;
 pub mod shipping_methods;
 pub mod shipping_zone_locations;
 pub mod shipping_zone_methods;
+pub mod shipping_zone_resolver;
 pub mod shipping_zones;
+pub mod store_settings;
+pub mod system_status;
 pub mod tax_classes;
+pub mod tax_engine;
 pub mod tax_rates;
 pub mod webhooks;
 #[skip_serializing_none]
@@ -31,6 +45,139 @@ pub struct MetaData {
     pub key: String,
     pub value: serde_json::Value,
 }
+/// Meta data of an entity with key based access.
+/// Keeps the entries as they were received, so only the changes are sent back to
+/// WooCommerce with [`MetaBag::payload`].
+#[derive(Debug, Clone, Default)]
+pub struct MetaBag {
+    items: Vec<MetaData>,
+    saved: Vec<MetaData>,
+}
+impl MetaBag {
+    pub fn new() -> Self {
+        MetaBag::default()
+    }
+    /// First value stored under the key. String values are also tried as JSON,
+    /// e.g. `"42"` can be read as a number.
+    pub fn get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.entries(key).find_map(|m| parse(&m.value))
+    }
+    /// All values stored under the key, for keys that are allowed to repeat.
+    pub fn get_all<T: serde::de::DeserializeOwned>(&self, key: &str) -> Vec<T> {
+        self.entries(key).filter_map(|m| parse(&m.value)).collect()
+    }
+    /// True if there is a value stored under the key.
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries(key).next().is_some()
+    }
+    /// Set the value of a key. The first existing entry is reused, duplicates are removed.
+    pub fn set(&mut self, key: impl Into<String>, value: impl serde::Serialize) -> &mut Self {
+        let key = key.into();
+        let value = serde_json::json!(value);
+        match self.items.iter().position(|m| m.key == key) {
+            Some(first) => {
+                self.items[first].value = value;
+                self.remove_where(|i, m| i != first && m.key == key);
+            }
+            None => self.items.push(MetaData {
+                id: None,
+                key,
+                value,
+            }),
+        }
+        self
+    }
+    /// Remove all values of a key.
+    pub fn remove(&mut self, key: &str) -> &mut Self {
+        self.remove_where(|_, m| m.key == key);
+        self
+    }
+    /// Entries to send with an update: changed values, new entries without id
+    /// and removed entries as id with a null value. Unchanged entries are left out.
+    pub fn payload(&self) -> Vec<MetaData> {
+        let mut payload = self
+            .items
+            .iter()
+            .filter(|m| match m.id {
+                Some(id) => !self
+                    .saved
+                    .iter()
+                    .any(|s| s.id == Some(id) && s.key == m.key && s.value == m.value),
+                None => true,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        // Saved entries dropped through `DerefMut` are removed as well.
+        payload.extend(
+            self.saved
+                .iter()
+                .filter(|s| !self.items.iter().any(|m| m.id == s.id))
+                .map(|s| MetaData {
+                    id: s.id,
+                    key: s.key.to_owned(),
+                    value: serde_json::Value::Null,
+                }),
+        );
+        payload
+    }
+    /// Add the payload to the meta data of an update, for the `meta_bag` builder setters.
+    pub(crate) fn extend_into(&self, meta_data: &mut Option<Vec<MetaData>>) {
+        meta_data.get_or_insert(vec![]).extend(self.payload());
+    }
+    fn entries<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a MetaData> {
+        self.items
+            .iter()
+            .filter(move |m| m.key == key && !m.value.is_null())
+    }
+    /// Saved entries are kept with a null value, so WooCommerce deletes them on update.
+    fn remove_where(&mut self, predicate: impl Fn(usize, &MetaData) -> bool) {
+        let mut i = 0;
+        self.items.retain_mut(|m| {
+            let matched = predicate(i, m);
+            i += 1;
+            if matched && m.id.is_some() {
+                m.value = serde_json::Value::Null;
+            }
+            !matched || m.id.is_some()
+        });
+    }
+}
+impl serde::Serialize for MetaBag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.items.serialize(serializer)
+    }
+}
+impl<'de> serde::Deserialize<'de> for MetaBag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        lenient::vec(deserializer).map(MetaBag::from)
+    }
+}
+fn parse<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> Option<T> {
+    serde_json::from_value(value.clone())
+        .ok()
+        .or_else(|| value.as_str().and_then(|s| serde_json::from_str(s).ok()))
+}
+/// Entries with an id are taken as saved in WooCommerce.
+impl From<Vec<MetaData>> for MetaBag {
+    fn from(value: Vec<MetaData>) -> Self {
+        MetaBag {
+            saved: value.iter().filter(|m| m.id.is_some()).cloned().collect(),
+            items: value,
+        }
+    }
+}
+impl std::ops::Deref for MetaBag {
+    type Target = Vec<MetaData>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+impl std::ops::DerefMut for MetaBag {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.items
+    }
+}
 #[skip_serializing_none]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BatchObject<O: serde::Serialize> {
@@ -103,3 +250,50 @@ where
         }
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved() -> MetaBag {
+        serde_json::from_value(serde_json::json!([
+            {"id": 1, "key": "color", "value": "blue"},
+            {"id": 2, "key": "size", "value": "42"},
+            {"id": 3, "key": "tag", "value": "a"},
+            {"id": 4, "key": "tag", "value": "b"},
+        ]))
+        .unwrap()
+    }
+    #[test]
+    fn unchanged_bag_has_empty_payload() {
+        assert!(saved().payload().is_empty());
+    }
+    #[test]
+    fn payload_has_only_changes() {
+        let mut bag = saved();
+        bag.set("color", "red").set("tag", "c").set("new", 1);
+        assert_eq!(bag.get::<i32>("size"), Some(42));
+        let payload = bag
+            .payload()
+            .into_iter()
+            .map(|m| (m.id, m.key, m.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            payload,
+            [
+                (Some(1), "color".into(), "red".into()),
+                (Some(3), "tag".into(), "c".into()),
+                (Some(4), "tag".into(), serde_json::Value::Null),
+                (None, "new".into(), 1.into()),
+            ]
+        );
+    }
+    #[test]
+    fn entries_dropped_through_deref_are_removed() {
+        let mut bag = saved();
+        bag.retain(|m| m.key != "size");
+        let payload = bag.payload();
+        assert_eq!(payload.len(), 1);
+        assert_eq!(payload[0].id, Some(2));
+        assert!(payload[0].value.is_null());
+    }
+}
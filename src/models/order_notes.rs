@@ -1,26 +1,37 @@
+use super::lenient;
 use crate::controllers::Entity;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::order_notes::{
     NoId, NoNote, OrderNotesCreateBuilder, OrderNotesUpdateBuilder,
 };
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderNotes {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Order note author    
+    #[serde(deserialize_with = "lenient::string")]
     pub author: String,
     /// The date the order note was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the order note was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// Order note content.
+    #[serde(deserialize_with = "lenient::string")]
     pub note: String,
     /// If true, the note will be shown to customers, and they will be notified. If false, the note will be for admin reference only. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub customer_note: bool,
     /// If true, this note will be attributed to the current user. If false, the note will be attributed to the system. Default is false.    
     pub added_by_user: Option<bool>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl OrderNotes {
     pub fn create() -> OrderNotesCreateBuilder<NoNote> {
@@ -29,6 +40,10 @@ impl OrderNotes {
     pub fn update() -> OrderNotesUpdateBuilder<NoId> {
         OrderNotesUpdateBuilder::<NoId>::new()
     }
+    /// The date the order note was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
 }
 impl Entity for OrderNotes {
     fn endpoint() -> String {
@@ -0,0 +1,1096 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use super::{
+    coupons::{Coupon, DiscountType},
+    orders::{Order, OrderTax},
+    product_variations::ProductVariation,
+    products::{Product, TaxStatus},
+    store_settings::StoreSettings,
+    tax_engine::{matching_rates, same_tax_class, TaxEngine, TaxLocation},
+    tax_rates::TaxRate,
+};
+
+/// Offline calculation of order totals, the way WooCommerce computes them
+/// when an order is created or recalculated.
+///
+/// # Example
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use rust_woocommerce::{ApiClient, Config, Coupon, OrderCalculator, Product, TaxRate};
+/// use tracing::info;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     tracing_subscriber::fmt::init();
+///     let config = Config::new("woo.toml")?;
+///     let client = ApiClient::new(&config)?;
+///     let settings = client.store_settings().await?;
+///     let product = client.retrieve::<Product>(3982).await?;
+///     let coupon = client.retrieve::<Coupon>(719).await?;
+///     let rates = client.list_all::<TaxRate>().await?;
+///     let totals = OrderCalculator::new()
+///         .settings(&settings)
+///         .tax_rates(&rates)
+///         .product(&product, 2)
+///         .shipping(10.0)
+///         .coupon(&coupon)
+///         .calculate();
+///     info!("Order total will be {}", settings.format_price(totals.total));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrderCalculator {
+    items: Vec<CalculatorItem>,
+    shipping_lines: Vec<f64>,
+    fee_lines: Vec<CalculatorFee>,
+    coupons: Vec<Coupon>,
+    tax_rates: Vec<TaxRate>,
+    tax_location: Option<TaxLocation>,
+    calc_taxes: bool,
+    prices_include_tax: bool,
+    tax_round_at_subtotal: bool,
+    calc_discounts_sequentially: bool,
+    shipping_tax_class: String,
+    decimals: u32,
+}
+/// Item of an order to calculate.
+#[derive(Debug, Clone, Default)]
+pub struct CalculatorItem {
+    /// Product ID.
+    pub product_id: i32,
+    /// Variation ID, if applicable.
+    pub variation_id: Option<i32>,
+    /// Quantity ordered.
+    pub quantity: i32,
+    /// Unit price, including tax if prices are entered with tax.
+    pub price: f64,
+    /// Line subtotal (before discounts), excluding tax. Used instead of the price if set.
+    pub subtotal: Option<f64>,
+    /// Line total (after discounts), excluding tax. Coupons don't apply to lines with a total.
+    pub total: Option<f64>,
+    /// Tax class, empty for the standard class.
+    pub tax_class: String,
+    /// Item is taxable.
+    pub taxable: bool,
+    /// Category IDs of the product, to check coupon restrictions.
+    pub category_ids: Vec<i32>,
+    /// Product is on sale, to check coupon restrictions.
+    pub on_sale: bool,
+}
+impl CalculatorItem {
+    /// Product at its current price.
+    pub fn product(product: &Product, quantity: i32) -> Self {
+        CalculatorItem {
+            product_id: product.id,
+            variation_id: None,
+            quantity,
+            price: amount(&product.price),
+            tax_class: product.tax_class.to_owned(),
+            taxable: matches!(product.tax_status, TaxStatus::Taxable),
+            category_ids: product.categories.iter().map(|c| c.id).collect(),
+            on_sale: product.on_sale,
+            ..Default::default()
+        }
+    }
+    /// Variation at its current price. The parent product gives the categories.
+    pub fn variation(product: &Product, variation: &ProductVariation, quantity: i32) -> Self {
+        CalculatorItem {
+            product_id: product.id,
+            variation_id: Some(variation.id),
+            quantity,
+            price: amount(&variation.price),
+            tax_class: variation.tax_class.to_owned(),
+            taxable: matches!(variation.tax_status, TaxStatus::Taxable),
+            category_ids: product.categories.iter().map(|c| c.id).collect(),
+            on_sale: variation.on_sale,
+            ..Default::default()
+        }
+    }
+}
+#[derive(Debug, Clone)]
+struct CalculatorFee {
+    total: f64,
+    tax_class: String,
+    taxable: bool,
+}
+impl Default for OrderCalculator {
+    fn default() -> Self {
+        OrderCalculator {
+            items: vec![],
+            shipping_lines: vec![],
+            fee_lines: vec![],
+            coupons: vec![],
+            tax_rates: vec![],
+            tax_location: None,
+            calc_taxes: true,
+            prices_include_tax: false,
+            tax_round_at_subtotal: false,
+            calc_discounts_sequentially: false,
+            shipping_tax_class: String::from("inherit"),
+            decimals: 2,
+        }
+    }
+}
+impl OrderCalculator {
+    pub fn new() -> Self {
+        OrderCalculator::default()
+    }
+    /// Lines of an existing order, to recalculate its taxes and totals.
+    /// Line subtotals and totals are kept, so coupons are not applied again.
+    /// The number of decimals is taken from the order total.
+    pub fn from_order(order: &Order) -> Self {
+        let mut calculator = OrderCalculator::new().decimals(decimals_of(&order.total));
+        for item in &order.line_items {
+            calculator.items.push(CalculatorItem {
+                product_id: item.product_id,
+                variation_id: item.variation_id.filter(|id| *id != 0),
+                quantity: item.quantity,
+                price: item.price,
+                subtotal: Some(amount(&item.subtotal)),
+                total: Some(amount(&item.total)),
+                tax_class: item.tax_class.to_owned(),
+                // Items that were not taxed are kept untaxed, as for non-taxable products.
+                taxable: !item.taxes.is_empty(),
+                ..Default::default()
+            });
+        }
+        for shipping in &order.shipping_lines {
+            calculator.shipping_lines.push(amount(&shipping.total));
+        }
+        for fee in &order.fee_lines {
+            calculator.fee_lines.push(CalculatorFee {
+                total: amount(&fee.total),
+                tax_class: fee.tax_class.to_owned(),
+                taxable: matches!(fee.tax_status, TaxStatus::Taxable),
+            });
+        }
+        calculator
+    }
+    /// Tax, rounding and discount settings of the store.
+    pub fn settings(mut self, settings: &StoreSettings) -> Self {
+        self.calc_taxes = settings.calc_taxes;
+        self.prices_include_tax = settings.prices_include_tax;
+        self.tax_round_at_subtotal = settings.tax_round_at_subtotal;
+        self.calc_discounts_sequentially = settings.calc_discounts_sequentially;
+        if !settings.shipping_tax_class.is_empty() {
+            self.shipping_tax_class = settings.shipping_tax_class.to_owned();
+        }
+        self.decimals = settings.price_decimals;
+        self
+    }
+    /// Tax rates of the store. Without a [`tax_location`](OrderCalculator::tax_location)
+    /// all of them are assumed to match the customer. The first rate of each priority is used,
+    /// like WooCommerce does.
+    pub fn tax_rates(mut self, tax_rates: &[TaxRate]) -> Self {
+        self.tax_rates.extend_from_slice(tax_rates);
+        self
+    }
+    /// Customer address the tax rates are matched against, see [`TaxEngine`].
+    pub fn tax_location(mut self, location: TaxLocation) -> Self {
+        let _ = self.tax_location.insert(location);
+        self
+    }
+    /// Prices are entered with tax. Default is false.
+    pub fn prices_include_tax(mut self, prices_include_tax: bool) -> Self {
+        self.prices_include_tax = prices_include_tax;
+        self
+    }
+    /// Number of decimals totals are rounded to. Default is 2.
+    pub fn decimals(mut self, decimals: u32) -> Self {
+        self.decimals = decimals;
+        self
+    }
+    /// Item with a unit price, in the standard tax class.
+    pub fn item(self, product_id: i32, quantity: i32, price: f64) -> Self {
+        self.line(CalculatorItem {
+            product_id,
+            quantity,
+            price,
+            taxable: true,
+            ..Default::default()
+        })
+    }
+    /// Item with all its details.
+    pub fn line(mut self, item: CalculatorItem) -> Self {
+        self.items.push(item);
+        self
+    }
+    /// Product at its current price.
+    pub fn product(self, product: &Product, quantity: i32) -> Self {
+        self.line(CalculatorItem::product(product, quantity))
+    }
+    /// Variation at its current price. The parent product gives the categories.
+    pub fn variation(self, product: &Product, variation: &ProductVariation, quantity: i32) -> Self {
+        self.line(CalculatorItem::variation(product, variation, quantity))
+    }
+    /// Shipping line cost, excluding tax.
+    pub fn shipping(mut self, total: f64) -> Self {
+        self.shipping_lines.push(total);
+        self
+    }
+    /// Fee line total, excluding tax.
+    pub fn fee(mut self, total: f64, tax_class: impl Into<String>, tax_status: TaxStatus) -> Self {
+        self.fee_lines.push(CalculatorFee {
+            total,
+            tax_class: tax_class.into(),
+            taxable: matches!(tax_status, TaxStatus::Taxable),
+        });
+        self
+    }
+    /// Coupon to apply, in the order coupons are added.
+    /// Product and category restrictions are respected, usage restrictions are not checked.
+    pub fn coupon(mut self, coupon: &Coupon) -> Self {
+        self.coupons.push(coupon.to_owned());
+        self
+    }
+    /// Calculate the totals of the order.
+    pub fn calculate(&self) -> OrderTotals {
+        let prices = self.prices();
+        let discounts = self.discounts(&prices);
+        let items = self
+            .items
+            .iter()
+            .zip(prices.iter().zip(discounts))
+            .map(|(item, (price, discount))| self.item_totals(item, *price, discount))
+            .collect::<Vec<_>>();
+        let shipping_rates = self.rates_for(&self.shipping_class(), true);
+        let shipping_lines = self
+            .shipping_lines
+            .iter()
+            .map(|total| self.line_totals(*total, *total, &shipping_rates, false))
+            .collect::<Vec<_>>();
+        let fee_lines = self
+            .fee_lines
+            .iter()
+            .map(|fee| {
+                let rates = match fee.taxable {
+                    true => self.rates_for(&fee.tax_class, false),
+                    false => vec![],
+                };
+                self.line_totals(fee.total, fee.total, &rates, false)
+            })
+            .collect::<Vec<_>>();
+        OrderTotals::aggregate(items, shipping_lines, fee_lines, self.decimals)
+    }
+    fn item_totals(&self, item: &CalculatorItem, price: f64, discount: f64) -> LineTotals {
+        let rates = match item.taxable {
+            true => self.rates_for(&item.tax_class, false),
+            false => vec![],
+        };
+        match item.total {
+            Some(total) => {
+                let subtotal = item.subtotal.unwrap_or(total);
+                self.line_totals(subtotal, total, &rates, false)
+            }
+            None => {
+                let subtotal = item.subtotal.unwrap_or(price);
+                let inclusive = self.prices_include_tax && item.subtotal.is_none();
+                self.line_totals(subtotal, subtotal - discount, &rates, inclusive)
+            }
+        }
+    }
+    fn line_totals(
+        &self,
+        subtotal: f64,
+        total: f64,
+        rates: &[&TaxRate],
+        inclusive: bool,
+    ) -> LineTotals {
+        let subtotal_taxes = self.round_line_taxes(TaxEngine::calc_tax(subtotal, rates, inclusive));
+        let taxes = self.round_line_taxes(TaxEngine::calc_tax(total, rates, inclusive));
+        let subtotal_tax = subtotal_taxes.values().sum::<f64>();
+        let total_tax = taxes.values().sum::<f64>();
+        LineTotals {
+            subtotal: if inclusive {
+                subtotal - subtotal_tax
+            } else {
+                subtotal
+            },
+            subtotal_tax,
+            total: if inclusive { total - total_tax } else { total },
+            total_tax,
+            subtotal_taxes,
+            taxes,
+        }
+    }
+    /// Line taxes are rounded, unless taxes are rounded at subtotal level.
+    fn round_line_taxes(&self, taxes: BTreeMap<i32, f64>) -> BTreeMap<i32, f64> {
+        match self.tax_round_at_subtotal {
+            true => taxes,
+            false => taxes
+                .into_iter()
+                .map(|(id, tax)| (id, round(tax, self.decimals)))
+                .collect(),
+        }
+    }
+    /// Rates of a tax class, one per priority, sorted by priority.
+    fn rates_for(&self, tax_class: &str, shipping: bool) -> Vec<&TaxRate> {
+        if !self.calc_taxes {
+            return vec![];
+        }
+        matching_rates(
+            &self.tax_rates,
+            self.tax_location.as_ref(),
+            tax_class,
+            shipping,
+        )
+    }
+    /// Shipping is taxed with the class of the items when the setting is `inherit`:
+    /// standard if any item uses it, the class of the first taxable item otherwise.
+    fn shipping_class(&self) -> String {
+        if self.shipping_tax_class != "inherit" {
+            return self.shipping_tax_class.to_owned();
+        }
+        let classes = self
+            .items
+            .iter()
+            .filter(|i| i.taxable)
+            .map(|i| i.tax_class.as_str())
+            .collect::<Vec<_>>();
+        match classes.iter().any(|c| same_tax_class(c, "")) {
+            true => String::new(),
+            false => classes.first().copied().unwrap_or_default().to_owned(),
+        }
+    }
+    /// Prices to discount, including tax if prices are entered with tax.
+    fn prices(&self) -> Vec<f64> {
+        self.items
+            .iter()
+            .map(|item| match item.total {
+                Some(_) => 0.0,
+                None => item.price * item.quantity as f64,
+            })
+            .collect()
+    }
+    /// Discount of every item.
+    fn discounts(&self, prices: &[f64]) -> Vec<f64> {
+        self.coupon_discounts(prices)
+            .into_iter()
+            .fold(vec![0.0; prices.len()], |total, discounts| {
+                total.iter().zip(discounts).map(|(a, b)| a + b).collect()
+            })
+    }
+    /// Discount of every item by each coupon, in the order coupons were added.
+    pub(crate) fn discounts_by_coupon(&self) -> Vec<Vec<f64>> {
+        self.coupon_discounts(&self.prices())
+    }
+    /// Discount of every item by each coupon, in the order coupons were added.
+    /// Works in the smallest currency unit, like WooCommerce.
+    fn coupon_discounts(&self, prices: &[f64]) -> Vec<Vec<f64>> {
+        let scale = 10_f64.powi(self.decimals as i32);
+        let original = prices
+            .iter()
+            .map(|p| (p * scale).round() as i64)
+            .collect::<Vec<_>>();
+        let mut discounts = vec![0_i64; prices.len()];
+        // Most expensive items are discounted first.
+        let mut sorted = (0..prices.len())
+            .filter(|i| self.items[*i].total.is_none())
+            .collect::<Vec<_>>();
+        sorted.sort_by_key(|i| std::cmp::Reverse(original[*i]));
+        let mut by_coupon = vec![];
+        for coupon in &self.coupons {
+            let before = discounts.clone();
+            let eligible = sorted
+                .iter()
+                .copied()
+                .filter(|i| original[*i] - discounts[*i] > 0)
+                .filter(|i| {
+                    matches!(coupon.discount_type, DiscountType::FixedCart)
+                        || coupon_applies(coupon, &self.items[*i])
+                })
+                .collect::<Vec<_>>();
+            let coupon_amount = amount(&coupon.amount);
+            let limit = coupon.limit_usage_to_x_items.filter(|l| *l > 0);
+            let mut applied = 0;
+            let mut apply_quantity = |quantity: i32| {
+                let apply = match limit {
+                    Some(limit) => (limit - applied).clamp(0, quantity),
+                    None => quantity,
+                };
+                applied += apply;
+                apply
+            };
+            match coupon.discount_type {
+                DiscountType::Percent => {
+                    let mut total_discount = 0;
+                    let mut cart_total = 0.0;
+                    for i in &eligible {
+                        let item = &self.items[*i];
+                        let discounted = original[*i] - discounts[*i];
+                        let base = match self.calc_discounts_sequentially {
+                            true => discounted,
+                            false => original[*i],
+                        };
+                        let quantity = item.quantity.max(1);
+                        let price_to_discount =
+                            base as f64 / quantity as f64 * apply_quantity(quantity) as f64;
+                        let discount = ((price_to_discount * coupon_amount / 100.0).floor() as i64)
+                            .min(discounted);
+                        cart_total += price_to_discount;
+                        total_discount += discount;
+                        discounts[*i] += discount;
+                    }
+                    let cart_discount = (cart_total * coupon_amount / 100.0).round() as i64;
+                    if total_discount < cart_discount {
+                        self.apply_remainder(
+                            &eligible,
+                            &original,
+                            &mut discounts,
+                            cart_discount - total_discount,
+                        );
+                    }
+                }
+                DiscountType::FixedProduct => {
+                    let unit_discount = (coupon_amount * scale).round() as i64;
+                    for i in &eligible {
+                        let discounted = original[*i] - discounts[*i];
+                        let quantity = self.items[*i].quantity.max(1);
+                        let discount = unit_discount * apply_quantity(quantity) as i64;
+                        discounts[*i] += discount.min(discounted);
+                    }
+                }
+                DiscountType::FixedCart => {
+                    let total = eligible
+                        .iter()
+                        .map(|i| original[*i] - discounts[*i])
+                        .sum::<i64>();
+                    let cart_discount = ((coupon_amount * scale).round() as i64).min(total);
+                    let mut total_discount = 0;
+                    for i in &eligible {
+                        let discounted = original[*i] - discounts[*i];
+                        let discount = (discounted as f64 / total as f64 * cart_discount as f64)
+                            .floor() as i64;
+                        let discount = discount.min(discounted);
+                        total_discount += discount;
+                        discounts[*i] += discount;
+                    }
+                    if total_discount < cart_discount {
+                        self.apply_remainder(
+                            &eligible,
+                            &original,
+                            &mut discounts,
+                            cart_discount - total_discount,
+                        );
+                    }
+                }
+                DiscountType::Other(_) => {}
+            }
+            by_coupon.push(
+                discounts
+                    .iter()
+                    .zip(before)
+                    .map(|(after, before)| (after - before) as f64 / scale)
+                    .collect(),
+            );
+        }
+        by_coupon
+    }
+    /// Rounding leftovers are given one unit per item quantity, until none is left.
+    fn apply_remainder(
+        &self,
+        eligible: &[usize],
+        original: &[i64],
+        discounts: &mut [i64],
+        mut remainder: i64,
+    ) {
+        for i in eligible {
+            for _ in 0..self.items[*i].quantity.max(1) {
+                if remainder <= 0 {
+                    return;
+                }
+                if original[*i] - discounts[*i] > 0 {
+                    discounts[*i] += 1;
+                    remainder -= 1;
+                }
+            }
+        }
+    }
+}
+/// Totals of an order, calculated with [`OrderCalculator`] or summed from the lines of an order.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrderTotals {
+    /// Totals of the line items.
+    pub line_items: Vec<LineTotals>,
+    /// Totals of the shipping lines.
+    pub shipping_lines: Vec<LineTotals>,
+    /// Totals of the fee lines.
+    pub fee_lines: Vec<LineTotals>,
+    /// Sum of line item subtotals (before discounts), excluding tax.
+    pub subtotal: f64,
+    /// Total discount amount.
+    pub discount_total: f64,
+    /// Total discount tax amount.
+    pub discount_tax: f64,
+    /// Total shipping amount.
+    pub shipping_total: f64,
+    /// Total shipping tax amount.
+    pub shipping_tax: f64,
+    /// Total fee amount.
+    pub fee_total: f64,
+    /// Sum of line item and fee taxes.
+    pub cart_tax: f64,
+    /// Sum of all taxes.
+    pub total_tax: f64,
+    /// Grand total.
+    pub total: f64,
+    /// Tax totals by tax rate ID.
+    pub tax_lines: BTreeMap<i32, TaxLineTotals>,
+    /// Number of decimals the totals are rounded to.
+    pub decimals: u32,
+}
+/// Subtotals, totals and taxes of one order line.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LineTotals {
+    /// Line subtotal (before discounts), excluding tax.
+    pub subtotal: f64,
+    /// Line subtotal tax (before discounts).
+    pub subtotal_tax: f64,
+    /// Line total (after discounts), excluding tax.
+    pub total: f64,
+    /// Line total tax (after discounts).
+    pub total_tax: f64,
+    /// Subtotal taxes by tax rate ID.
+    pub subtotal_taxes: BTreeMap<i32, f64>,
+    /// Total taxes by tax rate ID.
+    pub taxes: BTreeMap<i32, f64>,
+}
+/// Tax amounts of one tax rate.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TaxLineTotals {
+    /// Tax total of line items and fees.
+    pub tax_total: f64,
+    /// Shipping tax total.
+    pub shipping_tax_total: f64,
+}
+/// Total of an order that doesn't match the expected value.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TotalsDifference {
+    /// Name of the total, e.g. `total` or `line_items[0].total_tax`.
+    pub field: String,
+    /// Value calculated from the lines.
+    pub expected: f64,
+    /// Value found in the order.
+    pub actual: f64,
+}
+impl Display for TotalsDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, found {}",
+            self.field, self.expected, self.actual
+        )
+    }
+}
+impl OrderTotals {
+    /// Totals summed from the stored lines of an order, without recalculating taxes.
+    /// The number of decimals is taken from the order total.
+    pub fn from_order(order: &Order) -> Self {
+        let taxes = |taxes: &[OrderTax], subtotal: bool| {
+            taxes
+                .iter()
+                .map(|t| {
+                    let value = if subtotal { &t.subtotal } else { &t.total };
+                    (t.id, amount(value))
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+        let line_items = order
+            .line_items
+            .iter()
+            .map(|item| LineTotals {
+                subtotal: amount(&item.subtotal),
+                subtotal_tax: amount(&item.subtotal_tax),
+                total: amount(&item.total),
+                total_tax: amount(&item.total_tax),
+                subtotal_taxes: taxes(&item.taxes, true),
+                taxes: taxes(&item.taxes, false),
+            })
+            .collect();
+        let shipping_lines = order
+            .shipping_lines
+            .iter()
+            .map(|shipping| LineTotals {
+                subtotal: amount(&shipping.total),
+                subtotal_tax: amount(&shipping.total_tax),
+                total: amount(&shipping.total),
+                total_tax: amount(&shipping.total_tax),
+                subtotal_taxes: taxes(&shipping.taxes, false),
+                taxes: taxes(&shipping.taxes, false),
+            })
+            .collect();
+        let fee_lines = order
+            .fee_lines
+            .iter()
+            .map(|fee| LineTotals {
+                subtotal: amount(&fee.total),
+                subtotal_tax: amount(&fee.total_tax),
+                total: amount(&fee.total),
+                total_tax: amount(&fee.total_tax),
+                subtotal_taxes: taxes(&fee.taxes, false),
+                taxes: taxes(&fee.taxes, false),
+            })
+            .collect();
+        OrderTotals::aggregate(
+            line_items,
+            shipping_lines,
+            fee_lines,
+            decimals_of(&order.total),
+        )
+    }
+    fn aggregate(
+        line_items: Vec<LineTotals>,
+        shipping_lines: Vec<LineTotals>,
+        fee_lines: Vec<LineTotals>,
+        decimals: u32,
+    ) -> Self {
+        let sum = |lines: &[LineTotals], value: fn(&LineTotals) -> f64| -> f64 {
+            lines.iter().map(value).sum()
+        };
+        let mut cart_taxes = BTreeMap::<i32, f64>::new();
+        for line in line_items.iter().chain(&fee_lines) {
+            for (id, tax) in &line.taxes {
+                *cart_taxes.entry(*id).or_default() += tax;
+            }
+        }
+        let mut shipping_taxes = BTreeMap::<i32, f64>::new();
+        for line in &shipping_lines {
+            for (id, tax) in &line.taxes {
+                *shipping_taxes.entry(*id).or_default() += tax;
+            }
+        }
+        let mut tax_lines = BTreeMap::<i32, TaxLineTotals>::new();
+        for (id, tax) in &cart_taxes {
+            tax_lines.entry(*id).or_default().tax_total = round(*tax, decimals);
+        }
+        for (id, tax) in &shipping_taxes {
+            tax_lines.entry(*id).or_default().shipping_tax_total = round(*tax, decimals);
+        }
+        let cart_tax = round(tax_lines.values().map(|t| t.tax_total).sum(), decimals);
+        let shipping_tax = round(
+            tax_lines.values().map(|t| t.shipping_tax_total).sum(),
+            decimals,
+        );
+        let subtotal = sum(&line_items, |l| l.subtotal);
+        let items_total = sum(&line_items, |l| l.total);
+        let discount_tax = sum(&line_items, |l| l.subtotal_tax) - sum(&line_items, |l| l.total_tax);
+        let shipping_total = round(sum(&shipping_lines, |l| l.total), decimals);
+        let fee_total = sum(&fee_lines, |l| l.total);
+        OrderTotals {
+            subtotal: round(subtotal, decimals),
+            discount_total: round(subtotal - items_total, decimals),
+            discount_tax: round(discount_tax, decimals),
+            shipping_total,
+            shipping_tax,
+            fee_total: round(fee_total, decimals),
+            cart_tax,
+            total_tax: round(cart_tax + shipping_tax, decimals),
+            total: round(
+                items_total + fee_total + shipping_total + cart_tax + shipping_tax,
+                decimals,
+            ),
+            line_items,
+            shipping_lines,
+            fee_lines,
+            tax_lines,
+            decimals,
+        }
+    }
+    /// Totals of the order that differ from these totals.
+    /// Lines are compared by position when the order has the same number of lines.
+    pub fn diff(&self, order: &Order) -> Vec<TotalsDifference> {
+        let mut differences = vec![];
+        let mut compare = |field: String, expected: f64, actual: f64| {
+            let tolerance = 0.5 / 10_f64.powi(self.decimals as i32);
+            if (expected - actual).abs() >= tolerance {
+                differences.push(TotalsDifference {
+                    field,
+                    expected: round(expected, self.decimals),
+                    actual,
+                });
+            }
+        };
+        compare(
+            String::from("discount_total"),
+            self.discount_total,
+            amount(&order.discount_total),
+        );
+        compare(
+            String::from("discount_tax"),
+            self.discount_tax,
+            amount(&order.discount_tax),
+        );
+        compare(
+            String::from("shipping_total"),
+            self.shipping_total,
+            amount(&order.shipping_total),
+        );
+        compare(
+            String::from("shipping_tax"),
+            self.shipping_tax,
+            amount(&order.shipping_tax),
+        );
+        compare(
+            String::from("cart_tax"),
+            self.cart_tax,
+            amount(&order.cart_tax),
+        );
+        compare(
+            String::from("total_tax"),
+            self.total_tax,
+            amount(&order.total_tax),
+        );
+        compare(String::from("total"), self.total, amount(&order.total));
+        let mut order_tax_lines = BTreeMap::<i32, TaxLineTotals>::new();
+        for tax_line in &order.tax_lines {
+            let totals = order_tax_lines
+                .entry(tax_line.rate_id.parse().unwrap_or_default())
+                .or_default();
+            totals.tax_total += amount(&tax_line.tax_total);
+            totals.shipping_tax_total += amount(&tax_line.shipping_tax_total);
+        }
+        let rate_ids = self
+            .tax_lines
+            .keys()
+            .chain(order_tax_lines.keys())
+            .copied()
+            .collect::<std::collections::BTreeSet<_>>();
+        for id in rate_ids {
+            let expected = self.tax_lines.get(&id).cloned().unwrap_or_default();
+            let actual = order_tax_lines.get(&id).cloned().unwrap_or_default();
+            compare(
+                format!("tax_lines[{id}].tax_total"),
+                expected.tax_total,
+                actual.tax_total,
+            );
+            compare(
+                format!("tax_lines[{id}].shipping_tax_total"),
+                expected.shipping_tax_total,
+                actual.shipping_tax_total,
+            );
+        }
+        if self.line_items.len() == order.line_items.len() {
+            for (i, (expected, item)) in self.line_items.iter().zip(&order.line_items).enumerate() {
+                compare(
+                    format!("line_items[{i}].subtotal"),
+                    expected.subtotal,
+                    amount(&item.subtotal),
+                );
+                compare(
+                    format!("line_items[{i}].subtotal_tax"),
+                    expected.subtotal_tax,
+                    amount(&item.subtotal_tax),
+                );
+                compare(
+                    format!("line_items[{i}].total"),
+                    expected.total,
+                    amount(&item.total),
+                );
+                compare(
+                    format!("line_items[{i}].total_tax"),
+                    expected.total_tax,
+                    amount(&item.total_tax),
+                );
+            }
+        }
+        if self.shipping_lines.len() == order.shipping_lines.len() {
+            for (i, (expected, shipping)) in self
+                .shipping_lines
+                .iter()
+                .zip(&order.shipping_lines)
+                .enumerate()
+            {
+                compare(
+                    format!("shipping_lines[{i}].total_tax"),
+                    expected.total_tax,
+                    amount(&shipping.total_tax),
+                );
+            }
+        }
+        if self.fee_lines.len() == order.fee_lines.len() {
+            for (i, (expected, fee)) in self.fee_lines.iter().zip(&order.fee_lines).enumerate() {
+                compare(
+                    format!("fee_lines[{i}].total_tax"),
+                    expected.total_tax,
+                    amount(&fee.total_tax),
+                );
+            }
+        }
+        differences
+    }
+}
+impl Order {
+    /// Check that the totals of the order add up, e.g. after manual edits.
+    /// Line taxes are recalculated from the rates of the order tax lines, rounded per line,
+    /// and the order totals from the lines. Returns the totals that don't match.
+    /// Taxes of rates without `rate_percent` (before WooCommerce 3.7) are not recalculated.
+    pub fn check_totals(&self) -> Vec<TotalsDifference> {
+        let decimals = decimals_of(&self.total);
+        let rates = self
+            .tax_lines
+            .iter()
+            .filter_map(|t| {
+                Some(TaxRate {
+                    id: t.rate_id.parse().ok()?,
+                    rate: t.rate_percent?.to_string(),
+                    compound: t.compound,
+                    ..Default::default()
+                })
+            })
+            .collect::<Vec<_>>();
+        let line = |subtotal: f64, total: f64, taxes: &[OrderTax], has_subtotal: bool| {
+            let line_rates = rates
+                .iter()
+                .filter(|r| taxes.iter().any(|t| t.id == r.id))
+                .collect::<Vec<_>>();
+            let recalculate = |price: f64, subtotal: bool| {
+                let mut calculated = TaxEngine::calc_tax(price, &line_rates, false)
+                    .into_iter()
+                    .map(|(id, tax)| (id, round(tax, decimals)))
+                    .collect::<BTreeMap<_, _>>();
+                for tax in taxes {
+                    let stored = match subtotal && has_subtotal {
+                        true => &tax.subtotal,
+                        false => &tax.total,
+                    };
+                    calculated.entry(tax.id).or_insert_with(|| amount(stored));
+                }
+                calculated
+            };
+            let subtotal_taxes = recalculate(subtotal, true);
+            let taxes = recalculate(total, false);
+            LineTotals {
+                subtotal,
+                subtotal_tax: subtotal_taxes.values().sum(),
+                total,
+                total_tax: taxes.values().sum(),
+                subtotal_taxes,
+                taxes,
+            }
+        };
+        let line_items = self
+            .line_items
+            .iter()
+            .map(|i| line(amount(&i.subtotal), amount(&i.total), &i.taxes, true))
+            .collect();
+        let shipping_lines = self
+            .shipping_lines
+            .iter()
+            .map(|s| line(amount(&s.total), amount(&s.total), &s.taxes, false))
+            .collect();
+        let fee_lines = self
+            .fee_lines
+            .iter()
+            .map(|f| line(amount(&f.total), amount(&f.total), &f.taxes, false))
+            .collect();
+        OrderTotals::aggregate(line_items, shipping_lines, fee_lines, decimals).diff(self)
+    }
+}
+/// Restrictions on products, categories and sale items of product coupons.
+pub(crate) fn coupon_applies(coupon: &Coupon, item: &CalculatorItem) -> bool {
+    let ids = [item.product_id, item.variation_id.unwrap_or_default()];
+    (coupon.product_ids.is_empty() || ids.iter().any(|id| coupon.product_ids.contains(id)))
+        && !ids
+            .iter()
+            .any(|id| *id != 0 && coupon.excluded_product_ids.contains(id))
+        && (coupon.product_categories.is_empty()
+            || item
+                .category_ids
+                .iter()
+                .any(|c| coupon.product_categories.contains(c)))
+        && !item
+            .category_ids
+            .iter()
+            .any(|c| coupon.excluded_product_categories.contains(c))
+        && !(coupon.exclude_sale_items && item.on_sale)
+}
+fn decimals_of(total: &str) -> u32 {
+    total
+        .split_once('.')
+        .map(|(_, fraction)| fraction.len() as u32)
+        .unwrap_or_default()
+}
+fn amount(value: &str) -> f64 {
+    value.trim().parse().unwrap_or_default()
+}
+/// Round half away from zero, like PHP `round`.
+pub(crate) fn round(value: f64, decimals: u32) -> f64 {
+    let scale = 10_f64.powi(decimals as i32);
+    let scaled = value * scale;
+    (scaled + scaled.signum() * 1e-7).round() / scale
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coupon(discount_type: &str, amount: &str) -> Coupon {
+        serde_json::from_value(serde_json::json!({
+            "code": discount_type,
+            "discount_type": discount_type,
+            "amount": amount,
+        }))
+        .unwrap()
+    }
+    fn rate(id: i32, rate: &str) -> TaxRate {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "rate": rate,
+            "priority": 1,
+            "shipping": true,
+        }))
+        .unwrap()
+    }
+    fn order() -> Order {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "total": "132.00",
+            "total_tax": "22.00",
+            "cart_tax": "20.00",
+            "shipping_total": "10.00",
+            "shipping_tax": "2.00",
+            "discount_total": "0.00",
+            "discount_tax": "0.00",
+            "line_items": [
+                {
+                    "id": 11, "product_id": 5, "quantity": 1, "price": 100,
+                    "subtotal": "100.00", "subtotal_tax": "20.00",
+                    "total": "100.00", "total_tax": "20.00",
+                    "taxes": [{"id": 1, "subtotal": "20.00", "total": "20.00"}]
+                },
+                {
+                    "id": 12, "product_id": 6, "quantity": 1, "price": 0,
+                    "subtotal": "0.00", "subtotal_tax": "0.00",
+                    "total": "0.00", "total_tax": "0.00", "taxes": []
+                }
+            ],
+            "shipping_lines": [
+                {"id": 13, "total": "10.00", "total_tax": "2.00", "taxes": [{"id": 1, "total": "2.00"}]}
+            ],
+            "tax_lines": [
+                {"id": 14, "rate_id": "1", "rate_percent": 20, "compound": false,
+                 "tax_total": "20.00", "shipping_tax_total": "2.00"}
+            ]
+        }))
+        .unwrap()
+    }
+    #[test]
+    fn rounds_half_away_from_zero() {
+        assert_eq!(round(2.675, 2), 2.68);
+        assert_eq!(round(-1.005, 2), -1.01);
+        assert_eq!(round(0.125, 2), 0.13);
+        assert_eq!(round(1.4999, 0), 1.0);
+    }
+    #[test]
+    fn percent_coupons_use_original_prices_by_default() {
+        let calculator = OrderCalculator::new()
+            .item(1, 1, 100.0)
+            .item(2, 1, 50.0)
+            .coupon(&coupon("percent", "10"))
+            .coupon(&coupon("percent", "10"));
+        assert_eq!(calculator.discounts_by_coupon(), [[10.0, 5.0], [10.0, 5.0]]);
+        assert_eq!(calculator.calculate().discount_total, 30.0);
+    }
+    #[test]
+    fn percent_coupons_apply_sequentially() {
+        let settings = StoreSettings {
+            calc_discounts_sequentially: true,
+            price_decimals: 2,
+            ..Default::default()
+        };
+        let totals = OrderCalculator::new()
+            .settings(&settings)
+            .item(1, 1, 100.0)
+            .item(2, 1, 50.0)
+            .coupon(&coupon("percent", "10"))
+            .coupon(&coupon("percent", "10"))
+            .calculate();
+        assert_eq!(totals.discount_total, 28.5);
+        assert_eq!(totals.total, 121.5);
+    }
+    #[test]
+    fn limit_usage_to_x_items() {
+        let mut fixed = coupon("fixed_product", "2");
+        fixed.limit_usage_to_x_items = Some(2);
+        let mut percent = coupon("percent", "50");
+        percent.limit_usage_to_x_items = Some(1);
+        let calculator = OrderCalculator::new()
+            .item(1, 3, 10.0)
+            .coupon(&fixed)
+            .coupon(&percent);
+        assert_eq!(calculator.discounts_by_coupon(), [[4.0], [5.0]]);
+    }
+    #[test]
+    fn fixed_cart_remainder_goes_to_first_items() {
+        let calculator = OrderCalculator::new()
+            .item(1, 1, 10.0)
+            .item(2, 1, 10.0)
+            .item(3, 1, 10.0)
+            .coupon(&coupon("fixed_cart", "10"));
+        assert_eq!(calculator.discounts_by_coupon(), [[3.34, 3.33, 3.33]]);
+    }
+    #[test]
+    fn capped_percent_discount_leaves_remainder() {
+        let mut fixed = coupon("fixed_product", "9");
+        fixed.product_ids = vec![1];
+        let calculator = OrderCalculator::new()
+            .item(1, 1, 10.0)
+            .item(2, 1, 10.0)
+            .coupon(&fixed)
+            .coupon(&coupon("percent", "50"));
+        assert_eq!(calculator.discounts_by_coupon(), [[9.0, 0.0], [1.0, 5.01]]);
+    }
+    #[test]
+    fn taxes_items_and_shipping() {
+        let totals = OrderCalculator::new()
+            .tax_rates(&[rate(1, "20.0000")])
+            .item(1, 2, 50.0)
+            .shipping(10.0)
+            .calculate();
+        assert_eq!(totals.subtotal, 100.0);
+        assert_eq!(totals.cart_tax, 20.0);
+        assert_eq!(totals.shipping_tax, 2.0);
+        assert_eq!(totals.total, 132.0);
+        assert_eq!(totals.tax_lines[&1].tax_total, 20.0);
+    }
+    #[test]
+    fn inclusive_prices_are_split() {
+        let totals = OrderCalculator::new()
+            .prices_include_tax(true)
+            .tax_rates(&[rate(1, "20.0000")])
+            .item(1, 1, 12.0)
+            .calculate();
+        assert_eq!(totals.line_items[0].total, 10.0);
+        assert_eq!(totals.line_items[0].total_tax, 2.0);
+        assert_eq!(totals.total, 12.0);
+    }
+    #[test]
+    fn recalculating_an_order_keeps_untaxed_items_untaxed() {
+        let totals = OrderCalculator::from_order(&order())
+            .tax_rates(&[rate(1, "20.0000")])
+            .item(7, 1, 10.0)
+            .calculate();
+        assert_eq!(totals.line_items[0].total_tax, 20.0);
+        assert_eq!(totals.line_items[1].total_tax, 0.0);
+        assert_eq!(totals.line_items[2].total_tax, 2.0);
+        assert!(OrderTotals::from_order(&order()).diff(&order()).is_empty());
+    }
+    #[test]
+    fn check_totals_recalculates_line_taxes() {
+        assert!(order().check_totals().is_empty());
+        let mut order = order();
+        order.line_items[0].total_tax = String::from("18.00");
+        order.line_items[0].taxes[0].total = String::from("18.00");
+        let fields = order
+            .check_totals()
+            .into_iter()
+            .map(|d| d.field)
+            .collect::<Vec<_>>();
+        assert!(fields.contains(&String::from("line_items[0].total_tax")));
+        assert!(!fields.contains(&String::from("line_items[0].subtotal_tax")));
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use super::{order_notes::OrderNotes, orders::Order, orders::OrderStatus};
+
+/// Allowed order status transitions, used by
+/// [`ApiClient::transition_order`](crate::ApiClient::transition_order).
+/// Defaults follow the WooCommerce order lifecycle; refunded orders are final.
+///
+/// # Example
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use rust_woocommerce::{ApiClient, Config, OrderStatus, OrderWorkflow};
+/// use tracing::info;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     tracing_subscriber::fmt::init();
+///     let config = Config::new("woo.toml")?;
+///     let workflow = OrderWorkflow::new()
+///         .allow(OrderStatus::Processing, OrderStatus::Other("shipped".into()))
+///         .allow(OrderStatus::Other("shipped".into()), OrderStatus::Completed)
+///         .forbid(OrderStatus::Completed, OrderStatus::Processing);
+///     let client = ApiClient::new(&config)?.with_order_workflow(workflow);
+///     let transition = client
+///         .transition_order(12345, OrderStatus::Other("shipped".into()), "Picked up by courier")
+///         .await?;
+///     info!("Order {} is {}", transition.order.id, transition.order.status);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrderWorkflow {
+    transitions: Vec<(OrderStatus, OrderStatus)>,
+}
+/// Order moved to another status, with the note added.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderTransition {
+    /// Status before the transition.
+    pub from: OrderStatus,
+    /// Updated order.
+    pub order: Order,
+    /// Note added to the order, `None` if it couldn't be added.
+    pub note: Option<OrderNotes>,
+    /// Why the note couldn't be added. The order has moved all the same.
+    pub note_error: Option<String>,
+}
+/// Order the status update failed for, left in its status.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct OrderUpdateFailure {
+    /// Order ID.
+    pub order_id: i32,
+    /// Current status of the order.
+    pub from: OrderStatus,
+    /// Why the update failed.
+    pub error: String,
+}
+/// Status change not allowed by the workflow.
+/// Returned inside [`anyhow::Error`], use `error.downcast_ref::<OrderTransitionError>()` to inspect it.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct OrderTransitionError {
+    /// Order ID.
+    pub order_id: i32,
+    /// Current status of the order.
+    pub from: OrderStatus,
+    /// Requested status.
+    pub to: OrderStatus,
+}
+/// Result of a bulk transition.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkOrderTransition {
+    /// Orders moved to the new status.
+    pub transitioned: Vec<OrderTransition>,
+    /// Orders left unchanged, as the workflow doesn't allow the move.
+    pub rejected: Vec<OrderTransitionError>,
+    /// Orders left unchanged, as the update failed.
+    pub failed: Vec<OrderUpdateFailure>,
+}
+impl Display for OrderTransitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Order {} can't be moved from {} to {}",
+            self.order_id, self.from, self.to
+        )
+    }
+}
+impl std::error::Error for OrderTransitionError {}
+impl OrderTransition {
+    pub(crate) fn new(from: OrderStatus, order: Order, note: anyhow::Result<OrderNotes>) -> Self {
+        let (note, note_error) = match note {
+            Ok(note) => (Some(note), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        OrderTransition {
+            from,
+            order,
+            note,
+            note_error,
+        }
+    }
+}
+impl Display for OrderUpdateFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Order {} could not be moved from {}: {}",
+            self.order_id, self.from, self.error
+        )
+    }
+}
+impl Default for OrderWorkflow {
+    fn default() -> Self {
+        use OrderStatus::*;
+        let defaults = [
+            (Draft, vec![Pending, Processing, OnHold, Failed, Cancelled]),
+            (
+                Pending,
+                vec![Processing, OnHold, Completed, Cancelled, Failed],
+            ),
+            (
+                Failed,
+                vec![Pending, Processing, OnHold, Completed, Cancelled],
+            ),
+            (
+                OnHold,
+                vec![Pending, Processing, Completed, Cancelled, Failed],
+            ),
+            (Processing, vec![OnHold, Completed, Cancelled, Refunded]),
+            (Completed, vec![Processing, Refunded]),
+            (Cancelled, vec![Pending, Processing, OnHold]),
+        ];
+        OrderWorkflow {
+            transitions: defaults
+                .into_iter()
+                .flat_map(|(from, to)| to.into_iter().map(move |to| (from.clone(), to)))
+                .collect(),
+        }
+    }
+}
+impl OrderWorkflow {
+    /// Workflow with the WooCommerce default transitions.
+    pub fn new() -> Self {
+        OrderWorkflow::default()
+    }
+    /// Workflow without any allowed transition, to define all of them.
+    pub fn empty() -> Self {
+        OrderWorkflow {
+            transitions: vec![],
+        }
+    }
+    /// Allow moving orders from one status to another, e.g. to a status added by a plugin.
+    pub fn allow(mut self, from: OrderStatus, to: OrderStatus) -> Self {
+        if !self.can_transition(&from, &to) {
+            self.transitions.push((from, to));
+        }
+        self
+    }
+    /// Forbid moving orders from one status to another.
+    pub fn forbid(mut self, from: OrderStatus, to: OrderStatus) -> Self {
+        self.transitions.retain(|(f, t)| !(*f == from && *t == to));
+        self
+    }
+    /// True if orders can be moved from one status to the other.
+    /// Keeping the same status is not a transition.
+    pub fn can_transition(&self, from: &OrderStatus, to: &OrderStatus) -> bool {
+        from != to && self.transitions.iter().any(|(f, t)| f == from && t == to)
+    }
+    /// Statuses orders can be moved to from a status.
+    pub fn next_statuses(&self, from: &OrderStatus) -> Vec<&OrderStatus> {
+        self.transitions
+            .iter()
+            .filter(|(f, _)| f == from)
+            .map(|(_, t)| t)
+            .collect()
+    }
+    /// Check the status change of an order.
+    pub fn check(&self, order: &Order, to: &OrderStatus) -> Result<(), OrderTransitionError> {
+        match self.can_transition(&order.status, to) {
+            true => Ok(()),
+            false => Err(OrderTransitionError {
+                order_id: order.id,
+                from: order.status.to_owned(),
+                to: to.to_owned(),
+            }),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use OrderStatus::*;
+
+    fn order(status: &str) -> Order {
+        serde_json::from_value(serde_json::json!({ "id": 5, "status": status })).unwrap()
+    }
+    #[test]
+    fn default_transitions() {
+        let workflow = OrderWorkflow::new();
+        assert!(!workflow.can_transition(&Refunded, &Pending));
+        assert!(workflow.next_statuses(&Refunded).is_empty());
+        assert!(workflow.can_transition(&Completed, &Refunded));
+        assert!(workflow.can_transition(&Pending, &Processing));
+        assert!(!workflow.can_transition(&Completed, &Pending));
+        assert!(!workflow.can_transition(&Processing, &Processing));
+        assert_eq!(workflow.next_statuses(&Completed), [&Processing, &Refunded]);
+    }
+    #[test]
+    fn allow_and_forbid() {
+        let shipped = Other(String::from("shipped"));
+        let workflow = OrderWorkflow::new()
+            .allow(Processing, shipped.clone())
+            .allow(Processing, shipped.clone())
+            .allow(shipped.clone(), Completed)
+            .forbid(Completed, Processing);
+        assert!(workflow.can_transition(&Processing, &shipped));
+        assert!(workflow.can_transition(&shipped, &Completed));
+        assert!(!workflow.can_transition(&Completed, &Processing));
+        assert_eq!(
+            workflow
+                .next_statuses(&Processing)
+                .into_iter()
+                .filter(|s| **s == shipped)
+                .count(),
+            1
+        );
+        let workflow = OrderWorkflow::empty().allow(Pending, Cancelled);
+        assert_eq!(workflow.next_statuses(&Pending), [&Cancelled]);
+        assert!(!workflow.can_transition(&Pending, &Processing));
+    }
+    #[test]
+    fn check_reports_the_transition() {
+        let workflow = OrderWorkflow::new();
+        assert_eq!(workflow.check(&order("completed"), &Refunded), Ok(()));
+        let error = workflow.check(&order("refunded"), &Pending).unwrap_err();
+        assert_eq!(
+            error,
+            OrderTransitionError {
+                order_id: 5,
+                from: Refunded,
+                to: Pending,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Order 5 can't be moved from refunded to pending"
+        );
+        assert!(workflow.check(&order("pending"), &Pending).is_err());
+    }
+}
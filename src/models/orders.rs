@@ -1,101 +1,149 @@
-use crate::controllers::orders::{CreateOrderBuilder, UpdateOrderBuilder};
+use crate::controllers::orders::{
+    CreateOrderBuilder, OrderFeeLineCreate, SendOrderEmail, UpdateOrderBuilder,
+};
 
+use super::lenient;
 use super::{
     customers::{Billing, Shipping},
     data::CurrencyISO,
-    MetaData,
+    MetaBag,
 };
 use crate::controllers::Entity;
 use crate::TaxStatus;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Order {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Parent order ID.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub parent_id: Option<i32>,
     /// Order number.
+    #[serde(deserialize_with = "lenient::string")]
     pub number: String,
     /// Order key.
+    #[serde(deserialize_with = "lenient::string")]
     pub order_key: String,
     /// Shows where the order was created.
+    #[serde(deserialize_with = "lenient::string")]
     pub created_via: String,
     /// Version of WooCommerce which last updated the order.
+    #[serde(deserialize_with = "lenient::string")]
     pub version: String,
     /// Order status.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub status: OrderStatus,
     /// Currency the order was created with, in ISO format.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub currency: CurrencyISO,
     /// The date the order was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_created: Option<NaiveDateTime>,
     /// The date the order was created, as GMT.    
+    #[serde(deserialize_with = "lenient::option")]
     pub date_created_gmt: Option<NaiveDateTime>,
     ///The date the order was last modified, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_modified: Option<NaiveDateTime>,
     /// The date the order was last modified, as GMT
+    #[serde(deserialize_with = "lenient::option")]
     pub date_modified_gmt: Option<NaiveDateTime>,
     /// Total discount amount for the order.
+    #[serde(deserialize_with = "lenient::string")]
     pub discount_total: String,
     /// Total discount tax amount for the order.
+    #[serde(deserialize_with = "lenient::string")]
     pub discount_tax: String,
     /// Total shipping amount for the order.
+    #[serde(deserialize_with = "lenient::string")]
     pub shipping_total: String,
     /// Total shipping tax amount for the order.
+    #[serde(deserialize_with = "lenient::string")]
     pub shipping_tax: String,
     /// Sum of line item taxes only.
+    #[serde(deserialize_with = "lenient::string")]
     pub cart_tax: String,
     /// Grand total.
+    #[serde(deserialize_with = "lenient::string")]
     pub total: String,
     /// Sum of all taxes.
+    #[serde(deserialize_with = "lenient::string")]
     pub total_tax: String,
     /// True the prices included tax during checkout.
+    #[serde(deserialize_with = "lenient::bool")]
     pub prices_include_tax: bool,
     /// User ID who owns the order. 0 for guests. Default is 0.
+    #[serde(deserialize_with = "lenient::number")]
     pub customer_id: i32,
     /// Customer's IP address.
+    #[serde(deserialize_with = "lenient::string")]
     pub customer_ip_address: String,
     /// User agent of the customer.
+    #[serde(deserialize_with = "lenient::string")]
     pub customer_user_agent: String,
     /// Note left by customer during checkout.
+    #[serde(deserialize_with = "lenient::string")]
     pub customer_note: String,
     /// Billing address.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub billing: Billing,
     /// Shipping address.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub shipping: Shipping,
     /// Payment method ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub payment_method: String,
     /// Payment method title.
+    #[serde(deserialize_with = "lenient::string")]
     pub payment_method_title: String,
     /// Unique transaction ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub transaction_id: String,
     /// The date the order was paid, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_paid: Option<NaiveDateTime>,
     /// The date the order was paid, as GMT.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_paid_gmt: Option<NaiveDateTime>,
     /// The date the order was completed, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_completed: Option<NaiveDateTime>,
     /// The date the order was completed, as GMT.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_completed_gmt: Option<NaiveDateTime>,
     /// MD5 hash of cart items to ensure orders are not modified.
+    #[serde(deserialize_with = "lenient::string")]
     pub cart_hash: String,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
     /// Line items data.
+    #[serde(deserialize_with = "lenient::vec")]
     pub line_items: Vec<OrderLineItemProperties>,
     /// Tax lines data.
+    #[serde(deserialize_with = "lenient::vec")]
     pub tax_lines: Vec<OrderTaxLineProperties>,
     /// Shipping lines data.
+    #[serde(deserialize_with = "lenient::vec")]
     pub shipping_lines: Vec<ShippingLineProperties>,
     /// Fee lines data.
+    #[serde(deserialize_with = "lenient::vec")]
     pub fee_lines: Vec<OrderFeeLineProperties>,
     /// Coupons line data.
+    #[serde(deserialize_with = "lenient::vec")]
     pub coupon_lines: Vec<OrderCouponLineProperties>,
     /// List of refunds.
+    #[serde(deserialize_with = "lenient::vec")]
     pub refunds: Vec<OrderRefundProperties>,
     // Define if the order is paid. It will set the status to processing and reduce stock items. Default is false.
     // pub set_paid: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Order {
     pub fn create() -> CreateOrderBuilder {
@@ -104,6 +152,30 @@ impl Order {
     pub fn update() -> UpdateOrderBuilder {
         UpdateOrderBuilder::default()
     }
+    /// Options of the order emails sent with `send_order_details` and `send_email`.
+    pub fn email() -> SendOrderEmail {
+        SendOrderEmail::default()
+    }
+    /// Fee line of a new order, taxable in the standard tax class unless changed.
+    pub fn fee_line(name: impl Into<String>, total: impl Into<String>) -> OrderFeeLineCreate {
+        OrderFeeLineCreate::new(name, total)
+    }
+    /// The date the order was created, as UTC.
+    pub fn date_created_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_created_gmt.map(|d| d.and_utc())
+    }
+    /// The date the order was last modified, as UTC.
+    pub fn date_modified_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_modified_gmt.map(|d| d.and_utc())
+    }
+    /// The date the order was paid, as UTC.
+    pub fn date_paid_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_paid_gmt.map(|d| d.and_utc())
+    }
+    /// The date the order was completed, as UTC.
+    pub fn date_completed_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_completed_gmt.map(|d| d.and_utc())
+    }
 }
 impl Entity for Order {
     fn endpoint() -> String {
@@ -127,133 +199,232 @@ pub enum OrderStatus {
     Failed,
     Trash,
     Draft,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderStatus::Pending => write!(f, "pending"),
+            OrderStatus::Processing => write!(f, "processing"),
+            OrderStatus::OnHold => write!(f, "on-hold"),
+            OrderStatus::Completed => write!(f, "completed"),
+            OrderStatus::Cancelled => write!(f, "cancelled"),
+            OrderStatus::Refunded => write!(f, "refunded"),
+            OrderStatus::Failed => write!(f, "failed"),
+            OrderStatus::Trash => write!(f, "trash"),
+            OrderStatus::Draft => write!(f, "draft"),
+            OrderStatus::Other(status) => write!(f, "{status}"),
+        }
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderLineItemProperties {
     /// Item ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Product name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Product ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub product_id: i32,
     /// Variation ID, if applicable.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub variation_id: Option<i32>,
     /// Quantity ordered.
+    #[serde(deserialize_with = "lenient::number")]
     pub quantity: i32,
     /// Slug of the tax class of product.
+    #[serde(deserialize_with = "lenient::string")]
     pub tax_class: String,
     /// Line subtotal (before discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub subtotal: String,
     /// Line subtotal tax (before discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub subtotal_tax: String,
     /// Line total (after discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub total: String,
     /// Line total tax (after discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub total_tax: String,
     /// Line taxes.
+    #[serde(deserialize_with = "lenient::vec")]
     pub taxes: Vec<OrderTax>,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
     /// Product SKU.
+    #[serde(deserialize_with = "lenient::option")]
     pub sku: Option<String>,
     /// Product price.
+    #[serde(deserialize_with = "lenient::number")]
     pub price: f64,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderTaxLineProperties {
     /// Item ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Tax rate code.
+    #[serde(deserialize_with = "lenient::string")]
     pub rate_code: String,
     /// Tax rate ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub rate_id: String,
     /// Tax rate label.
+    #[serde(deserialize_with = "lenient::string")]
     pub label: String,
     /// Show if is a compound tax rate.
+    #[serde(deserialize_with = "lenient::bool")]
     pub compound: bool,
+    /// Tax rate percentage, sent since WooCommerce 3.7.
+    #[serde(deserialize_with = "lenient::option_number")]
+    pub rate_percent: Option<f64>,
     /// Tax total (not including shipping taxes).
+    #[serde(deserialize_with = "lenient::string")]
     pub tax_total: String,
     /// Shipping tax total.
+    #[serde(deserialize_with = "lenient::string")]
     pub shipping_tax_total: String,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingLineProperties {
     /// Item ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Shipping method name.
+    #[serde(deserialize_with = "lenient::string")]
     pub method_title: String,
     /// Shipping method ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub method_id: String,
     /// Line total (after discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub total: String,
     /// Line total tax (after discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub total_tax: String,
     /// Line taxes.
+    #[serde(deserialize_with = "lenient::vec")]
     pub taxes: Vec<OrderTax>,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderFeeLineProperties {
     /// Item ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Fee name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Tax class of fee.
+    #[serde(deserialize_with = "lenient::string")]
     pub tax_class: String,
     /// Tax status of fee. Options: taxable and none.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub tax_status: TaxStatus,
     /// Line total (after discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub total: String,
     /// Line total tax (after discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub total_tax: String,
     /// Line taxes.
+    #[serde(deserialize_with = "lenient::vec")]
     pub taxes: Vec<OrderTax>,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderCouponLineProperties {
     /// Item ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Coupon code.
+    #[serde(deserialize_with = "lenient::string")]
     pub code: String,
     /// Discount total.
+    #[serde(deserialize_with = "lenient::string")]
     pub discount: String,
     /// Discount total tax.
+    #[serde(deserialize_with = "lenient::string")]
     pub discount_tax: String,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderRefundProperties {
     /// Refund ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Refund reason.
+    #[serde(deserialize_with = "lenient::string")]
     pub reason: String,
     /// Refund total.
+    #[serde(deserialize_with = "lenient::string")]
     pub total: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderTax {
-    /// Item ID.
-    pub id: i32,
-    /// Tax rate code.
-    pub rate_code: String,
     /// Tax rate ID.
-    pub rate_id: String,
-    /// Tax rate label.
-    pub label: String,
-    /// Show if is a compound tax rate.
-    pub compound: bool,
-    /// Tax total (not including shipping taxes).
-    pub tax_total: String,
-    /// Shipping tax total.
-    pub shipping_tax_total: String,
-    /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    #[serde(deserialize_with = "lenient::number")]
+    pub id: i32,
+    /// Tax total of the line, after discounts.
+    #[serde(deserialize_with = "lenient::string")]
+    pub total: String,
+    /// Tax subtotal of the line, before discounts. Only set for line items.
+    #[serde(deserialize_with = "lenient::string")]
+    pub subtotal: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct OrderEmailTemplate {
+    /// Email template ID.
+    #[serde(deserialize_with = "lenient::string")]
+    pub id: String,
+    /// Email template title.
+    #[serde(deserialize_with = "lenient::string")]
+    pub title: String,
+    /// Email template description.
+    #[serde(deserialize_with = "lenient::string")]
+    pub description: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -1,27 +1,43 @@
+use std::collections::BTreeMap;
+
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::payment_gateways::PaymentGatewayUpdate;
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct PaymentGateway {
     /// Payment gateway ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub id: String,
     /// Payment gateway title on checkout.
+    #[serde(deserialize_with = "lenient::string")]
     pub title: String,
     /// Payment gateway description on checkout.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Payment gateway sort order.
+    #[serde(deserialize_with = "lenient::string")]
     pub order: String,
     /// Payment gateway enabled status.
+    #[serde(deserialize_with = "lenient::bool")]
     pub enabled: bool,
     /// Payment gateway method title.
+    #[serde(deserialize_with = "lenient::string")]
     pub method_title: String,
     /// Payment gateway method description.
+    #[serde(deserialize_with = "lenient::string")]
     pub method_description: String,
     /// Supported features for this payment gateway.
+    #[serde(deserialize_with = "lenient::vec")]
     pub method_supports: Vec<String>,
     /// Payment gateway settings.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub settings: PaymentGatewaySettings,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for PaymentGateway {
     fn endpoint() -> String {
@@ -35,40 +51,127 @@ impl Entity for PaymentGateway {
 }
 impl PaymentGateway {
     pub fn turn_on() -> PaymentGatewayUpdate {
-        PaymentGatewayUpdate { enabled: true }
+        PaymentGatewayUpdate::default().enabled()
     }
     pub fn turn_off() -> PaymentGatewayUpdate {
-        PaymentGatewayUpdate { enabled: false }
+        PaymentGatewayUpdate::default().disabled()
+    }
+    /// Update of title, description, order, enabled status and any settings values.
+    pub fn update() -> PaymentGatewayUpdate {
+        PaymentGatewayUpdate::default()
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct PaymentGatewaySettings {
-    pub title: TitleInstructions,
-    pub instructions: TitleInstructions,
+    /// Title shown on checkout.
+    #[serde(deserialize_with = "lenient::option")]
+    pub title: Option<PaymentGatewaySetting>,
+    /// Description shown on checkout.
+    #[serde(deserialize_with = "lenient::option")]
+    pub description: Option<PaymentGatewaySetting>,
+    /// Instructions added to the thank you page and emails.
+    #[serde(deserialize_with = "lenient::option")]
+    pub instructions: Option<PaymentGatewaySetting>,
+    /// Shipping methods the gateway is available for (cash on delivery).
+    #[serde(deserialize_with = "lenient::option")]
+    pub enable_for_methods: Option<PaymentGatewaySetting>,
+    /// Gateway is available for virtual orders (cash on delivery).
+    #[serde(deserialize_with = "lenient::option")]
+    pub enable_for_virtual: Option<PaymentGatewaySetting>,
+    /// Settings not covered by the model, by setting id.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, PaymentGatewaySetting>,
+}
+impl PaymentGatewaySettings {
+    /// Any setting by its id.
+    pub fn get(&self, id: &str) -> Option<&PaymentGatewaySetting> {
+        match id {
+            "title" => self.title.as_ref(),
+            "description" => self.description.as_ref(),
+            "instructions" => self.instructions.as_ref(),
+            "enable_for_methods" => self.enable_for_methods.as_ref(),
+            "enable_for_virtual" => self.enable_for_virtual.as_ref(),
+            _ => self.extra.get(id),
+        }
+    }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TitleInstructions {
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PaymentGatewaySetting {
     /// A unique identifier for the setting.
+    #[serde(deserialize_with = "lenient::string")]
     pub id: String,
     /// A human readable label for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
     pub label: String,
     /// A human readable description for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Type of setting. Options: text, email, number, color, password, textarea, select, multiselect, radio, image_width and checkbox.
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "lenient::or_default")]
     pub setting_type: SettingType,
     /// Setting value.
-    pub value: String,
+    pub value: serde_json::Value,
     /// Default value for the setting.
-    pub default: String,
+    pub default: serde_json::Value,
     /// Additional help text shown to the user about the setting.
+    #[serde(deserialize_with = "lenient::string")]
     pub tip: String,
     /// Placeholder text to be displayed in text inputs.
+    #[serde(deserialize_with = "lenient::string")]
     pub placeholder: String,
+    /// Available options for select, multiselect and radio settings.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub options: serde_json::Map<String, serde_json::Value>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl PaymentGatewaySetting {
+    /// Setting value read according to the setting type.
+    pub fn typed_value(&self) -> SettingValue {
+        match (&self.setting_type, &self.value) {
+            (SettingType::Checkbox, value) => SettingValue::Checkbox(value.as_str() == Some("yes")),
+            (SettingType::Multiselect, serde_json::Value::Array(values)) => SettingValue::List(
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect(),
+            ),
+            (SettingType::Multiselect, serde_json::Value::String(value)) if value.is_empty() => {
+                SettingValue::List(vec![])
+            }
+            (SettingType::Number, value) => match value {
+                serde_json::Value::Number(n) => n.as_f64().map(SettingValue::Number),
+                serde_json::Value::String(s) => s.parse().ok().map(SettingValue::Number),
+                _ => None,
+            }
+            .unwrap_or(SettingValue::Other(value.clone())),
+            (_, serde_json::Value::String(value)) => SettingValue::Text(value.clone()),
+            (_, value) => SettingValue::Other(value.clone()),
+        }
+    }
+}
+/// Setting value by kind of setting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingValue {
+    /// Text, textarea, select, radio, email, password and color settings.
+    Text(String),
+    /// Checkbox settings, stored as `"yes"` or `"no"`.
+    Checkbox(bool),
+    /// Number settings.
+    Number(f64),
+    /// Multiselect settings.
+    List(Vec<String>),
+    /// Values not matching the setting type.
+    Other(serde_json::Value),
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum SettingType {
+    #[default]
     Text,
     Email,
     Number,
@@ -81,4 +184,7 @@ pub enum SettingType {
     ImageWidth,
     Checkbox,
     SafeText,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
@@ -1,23 +1,34 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::product_attribute_terms::{
     AttributeTermCreateBuilder, AttributeTermUpdateBuilder, NoName,
 };
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct AttributeTerm {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Term name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// An alphanumeric identifier for the resource unique to its type.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
     /// HTML description of the resource.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Menu order, used to custom sort the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub menu_order: i32,
     /// Number of published products for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub count: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for AttributeTerm {
     fn endpoint() -> String {
@@ -1,3 +1,4 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
@@ -7,21 +8,31 @@ use crate::controllers::product_attributes::{
 use crate::controllers::products::{AttributeDTOBuilder, NoOptions};
 
 /// Product attribute properties
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Attribute {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Attribute name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// An alphanumeric identifier for the resource unique to its type.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
     /// Type of attribute. By default, only select is supported.
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "lenient::or_default")]
     pub attribute_type: AttributeType,
     /// Default sort order. Options: menu_order, name, name_num and id. Default is menu_order.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub order_by: AttributeSortOrder,
     /// Enable/Disable attribute archives. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub has_archives: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for Attribute {
     fn endpoint() -> String {
@@ -50,6 +61,9 @@ impl Attribute {
 pub enum AttributeType {
     #[default]
     Select,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -59,4 +73,7 @@ pub enum AttributeSortOrder {
     Name,
     NameNum,
     Id,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
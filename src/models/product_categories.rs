@@ -1,29 +1,43 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::product_categories::{CategoryCreate, CategoryUpdate};
 
 use super::products::ProductImage;
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Category {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Category name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// An alphanumeric identifier for the resource unique to its type.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
     /// The ID for the parent of the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub parent: i32,
     /// HTML description of the resource.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Category archive display type. Options: default, products, subcategories and both. Default is default.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub display: DisplayOption,
     /// Image data.
+    #[serde(deserialize_with = "lenient::option")]
     pub image: Option<ProductImage>,
     /// Menu order, used to custom sort the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub menu_order: i32,
     /// Number of published products for the resource. READ-ONLY
+    #[serde(deserialize_with = "lenient::number")]
     pub count: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Category {
     pub fn create<T: ToString>(name: T) -> CategoryCreate {
@@ -51,4 +65,7 @@ pub enum DisplayOption {
     Products,
     Subcategories,
     Both,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
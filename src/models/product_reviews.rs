@@ -1,32 +1,47 @@
+use super::lenient;
 use crate::controllers::Entity;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::product_reviews::{
     NoEmail, NoId, ProductReviewCreateBuilder, ProductReviewUpdateBuilder,
 };
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ProductReview {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// The date the review was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the review was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// Unique identifier for the product that the review belongs to.
+    #[serde(deserialize_with = "lenient::number")]
     pub product_id: i32,
     /// Status of the review. Options: approved, hold, spam, unspam, trash and untrash. Defaults to approved.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub status: ReviewStatus,
     /// Reviewer name.
+    #[serde(deserialize_with = "lenient::string")]
     pub reviewer: String,
     /// Reviewer email.
+    #[serde(deserialize_with = "lenient::string")]
     pub reviewer_email: String,
     /// The content of the review.
+    #[serde(deserialize_with = "lenient::string")]
     pub review: String,
     /// Review rating (0 to 5).
+    #[serde(deserialize_with = "lenient::number")]
     pub rating: i32,
     /// Shows if the reviewer bought the product or not.
+    #[serde(deserialize_with = "lenient::bool")]
     pub verified: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for ProductReview {
     fn endpoint() -> String {
@@ -45,6 +60,10 @@ impl ProductReview {
     pub fn update() -> ProductReviewUpdateBuilder {
         ProductReviewUpdateBuilder::default()
     }
+    /// The date the review was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -56,4 +75,7 @@ pub enum ReviewStatus {
     Unspam,
     Trash,
     Untrash,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
@@ -1,21 +1,31 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::product_shipping_classes::{
     NoName, ShippingClassCreateBuilder, ShippingClassUpdateBuilder,
 };
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingClass {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Shipping class name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// An alphanumeric identifier for the resource unique to its type.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
     /// HTML description of the resource.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Number of published products for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub count: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for ShippingClass {
     fn endpoint() -> String {
@@ -1,99 +1,160 @@
-use crate::controllers::product_variations::ProductVariationModifyBuilder;
+use crate::controllers::product_variations::{
+    same_attribute_name, same_option, ProductVariationModifyBuilder, VariationTemplate,
+};
 
+use super::lenient;
 use super::{
     products::{
         BackordersStatus, Dimensions, Download, ProductDefaultAttribute, ProductImage,
         ProductStatus, StockStatus, TaxStatus,
     },
-    MetaData,
+    MetaBag,
 };
 use crate::controllers::Entity;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ProductVariation {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// The date the variation was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the variation was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// The date the variation was last modified, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified: NaiveDateTime,
     /// The date the variation was last modified, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified_gmt: NaiveDateTime,
     /// Variation description.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Variation URL.
+    #[serde(deserialize_with = "lenient::string")]
     pub permalink: String,
     /// Unique identifier.
+    #[serde(deserialize_with = "lenient::string")]
     pub sku: String,
     /// Current variation price.
+    #[serde(deserialize_with = "lenient::string")]
     pub price: String,
     /// Variation regular price.
+    #[serde(deserialize_with = "lenient::string")]
     pub regular_price: String,
     /// Variation sale price.
+    #[serde(deserialize_with = "lenient::string")]
     pub sale_price: String,
     /// Start date of sale price, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_from: Option<NaiveDateTime>,
     /// Start date of sale price, as GMT.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_from_gmt: Option<NaiveDateTime>,
     /// End date of sale price, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_to: Option<NaiveDateTime>,
     /// End date of sale price, as GMT.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_to_gmt: Option<NaiveDateTime>,
     /// Shows if the variation is on sale. READ-ONLY
+    #[serde(deserialize_with = "lenient::bool")]
     pub on_sale: bool,
     /// Variation status. Options: draft, pending, private and publish. Default is publish.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub status: ProductStatus,
     /// Shows if the variation can be bought.
+    #[serde(deserialize_with = "lenient::bool")]
     pub purchasable: bool,
     /// If the variation is virtual. Default is false.
     #[serde(rename = "virtual")]
+    #[serde(deserialize_with = "lenient::bool")]
     pub is_virtual: bool,
     /// If the variation is downloadable. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub downloadable: bool,
     /// List of downloadable files.
+    #[serde(deserialize_with = "lenient::vec")]
     pub downloads: Vec<Download>,
     /// Number of times downloadable files can be downloaded after purchase. Default is -1.
+    #[serde(deserialize_with = "lenient::number")]
     pub download_limit: i32,
     /// Number of days until access to downloadable files expires. Default is -1.
+    #[serde(deserialize_with = "lenient::number")]
     pub download_expiry: i32,
     /// Tax status. Options: taxable, shipping and none. Default is taxable.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub tax_status: TaxStatus,
     /// Tax class.
+    #[serde(deserialize_with = "lenient::string")]
     pub tax_class: String,
     /// Stock management at variation level. Default is false.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub manage_stock: ManageStock,
     /// Stock quantity.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub stock_quantity: Option<i32>,
     /// Controls the stock status of the product. Options: instock, outofstock, onbackorder. Default is instock.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub stock_status: StockStatus,
     /// If managing stock, this controls if backorders are allowed. Options: no, notify and yes. Default is no.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub backorders: BackordersStatus,
     /// Shows if backorders are allowed.
+    #[serde(deserialize_with = "lenient::bool")]
     pub backorders_allowed: bool,
     /// Shows if the variation is on backordered.
+    #[serde(deserialize_with = "lenient::bool")]
     pub backordered: bool,
     /// Variation weight.
+    #[serde(deserialize_with = "lenient::string")]
     pub weight: String,
     /// Variation dimensions. See Product variation - Dimensions properties
+    #[serde(deserialize_with = "lenient::or_default")]
     pub dimensions: Dimensions,
     /// Shipping class slug.
+    #[serde(deserialize_with = "lenient::string")]
     pub shipping_class: String,
     /// Shipping class ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub shipping_class_id: i32,
     /// Variation image data.
+    #[serde(deserialize_with = "lenient::option")]
     pub image: Option<ProductImage>,
     /// List of attributes.
+    #[serde(deserialize_with = "lenient::vec")]
     pub attributes: Vec<ProductDefaultAttribute>,
     /// Menu order, used to custom sort products.
+    #[serde(deserialize_with = "lenient::number")]
     pub menu_order: i32,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
+/// Variations created by [`ApiClient::generate_variations`](crate::ApiClient::generate_variations).
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedVariations {
+    /// Variations that were created.
+    pub created: Vec<ProductVariation>,
+    /// Combinations the batch request failed to create a variation for.
+    pub failed: Vec<FailedVariation>,
+}
+/// Variation that couldn't be created, e.g. for a duplicate SKU.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FailedVariation {
+    /// Options of the variation, as `(attribute name, option)` pairs.
+    pub options: Vec<(String, String)>,
+    /// Why the variation wasn't created.
+    pub error: String,
+}
 impl Entity for ProductVariation {
     fn endpoint() -> String {
         String::new()
@@ -107,6 +168,51 @@ impl ProductVariation {
     pub fn builder() -> ProductVariationModifyBuilder {
         ProductVariationModifyBuilder::default()
     }
+    /// Template to generate variations from the product attributes.
+    pub fn template() -> VariationTemplate {
+        VariationTemplate::new()
+    }
+    /// Variation matching the selected attributes, as `(name, option)` pairs, e.g. `("Color", "Red")`.
+    /// Attributes of the variation with an empty option accept any value.
+    /// Like WooCommerce, the first match by menu order wins.
+    /// Use [`Product::match_variation`](crate::Product::match_variation) to fill the selection with the default attributes.
+    pub fn match_variation<'a>(
+        variations: &'a [ProductVariation],
+        attributes: &[(&str, &str)],
+    ) -> Option<&'a ProductVariation> {
+        variations
+            .iter()
+            .filter(|v| v.matches(attributes))
+            .min_by_key(|v| (v.menu_order, v.id))
+    }
+    /// True if the variation accepts the selected attributes.
+    pub fn matches(&self, attributes: &[(&str, &str)]) -> bool {
+        self.attributes
+            .iter()
+            .filter(|a| !a.option.is_empty())
+            .all(|a| {
+                attributes
+                    .iter()
+                    .find(|(name, _)| same_attribute_name(&a.name, name))
+                    .is_some_and(|(_, option)| same_option(&a.option, option))
+            })
+    }
+    /// The date the variation was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
+    /// The date the variation was last modified, as UTC.
+    pub fn date_modified_utc(&self) -> DateTime<Utc> {
+        self.date_modified_gmt.and_utc()
+    }
+    /// Start date of sale price, as UTC.
+    pub fn date_on_sale_from_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_on_sale_from_gmt.map(|d| d.and_utc())
+    }
+    /// End date of sale price, as UTC.
+    pub fn date_on_sale_to_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_on_sale_to_gmt.map(|d| d.and_utc())
+    }
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -114,3 +220,79 @@ pub enum ManageStock {
     Bool(bool),
     Parent(String),
 }
+impl Default for ManageStock {
+    fn default() -> Self {
+        ManageStock::Bool(false)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Product;
+
+    fn variation(id: i32, menu_order: i32, color: &str, size: &str) -> ProductVariation {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "menu_order": menu_order,
+            "attributes": [
+                {"id": 1, "name": "Color", "option": color},
+                {"id": 0, "name": "Size", "option": size}
+            ]
+        }))
+        .unwrap()
+    }
+    #[test]
+    fn empty_option_matches_any_value() {
+        let any_size = variation(1, 0, "Red", "");
+        assert!(any_size.matches(&[("pa_color", "red"), ("Size", "XL")]));
+        assert!(any_size.matches(&[("Color", "Red")]));
+        assert!(!any_size.matches(&[("Color", "Blue"), ("Size", "XL")]));
+        let exact = variation(2, 0, "Dark Red", "XL");
+        assert!(exact.matches(&[("attribute_pa_color", "dark-red"), ("size", "xl")]));
+        assert!(!exact.matches(&[("Color", "Dark Red")]));
+    }
+    #[test]
+    fn ties_broken_by_menu_order_then_id() {
+        let variations = [
+            variation(30, 2, "Red", ""),
+            variation(20, 1, "", "XL"),
+            variation(10, 1, "Red", "XL"),
+        ];
+        let selection = [("Color", "Red"), ("Size", "XL")];
+        let matched = ProductVariation::match_variation(&variations, &selection);
+        assert_eq!(matched.map(|v| v.id), Some(10));
+        let matched =
+            ProductVariation::match_variation(&variations, &[("Color", "Red"), ("Size", "S")]);
+        assert_eq!(matched.map(|v| v.id), Some(30));
+        assert!(ProductVariation::match_variation(
+            &variations,
+            &[("Color", "Blue"), ("Size", "S")]
+        )
+        .is_none());
+    }
+    #[test]
+    fn selection_filled_from_default_attributes() {
+        let product: Product = serde_json::from_value(serde_json::json!({
+            "id": 5,
+            "default_attributes": [
+                {"id": 1, "name": "Color", "option": "blue"},
+                {"id": 0, "name": "Size", "option": "M"}
+            ]
+        }))
+        .unwrap();
+        let variations = [
+            variation(1, 0, "Blue", "S"),
+            variation(2, 0, "Blue", "M"),
+            variation(3, 0, "Red", "M"),
+        ];
+        let matched = |selection: &[(&str, &str)]| {
+            product
+                .match_variation(&variations, selection)
+                .map(|v| v.id)
+        };
+        assert_eq!(matched(&[]), Some(2));
+        assert_eq!(matched(&[("Size", "S")]), Some(1));
+        assert_eq!(matched(&[("pa_color", "Red")]), Some(3));
+        assert_eq!(matched(&[("Color", "Red"), ("Size", "S")]), None);
+    }
+}
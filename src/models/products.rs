@@ -1,145 +1,215 @@
 use crate::controllers::{
+    product_variations::{same_attribute_name, VariationAttribute},
     products::{ProductModify, ProductModifyBuilder},
     Entity,
 };
 
-use super::MetaData;
-use chrono::NaiveDateTime;
+use super::lenient;
+use super::{product_variations::ProductVariation, MetaBag};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Product {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Product name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Product slug.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
     /// Product URL.
+    #[serde(deserialize_with = "lenient::string")]
     pub permalink: String,
     /// The date the product was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the product was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// The date the product was last modified, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified: NaiveDateTime,
     /// The date the product was last modified, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified_gmt: NaiveDateTime,
     /// Product type, Options: simple, grouped, external and variable. Default is simple.
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "lenient::or_default")]
     pub product_type: ProductType,
     /// Product status (post status). Options: draft, pending, private and publish. Default is publish.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub status: ProductStatus,
     /// Featured product. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub featured: bool,
     /// Catalog visibility. Options: visible, catalog, search and hidden. Default is visible.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub catalog_visibility: CatalogVisibility,
     /// Product description.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Product short description.
+    #[serde(deserialize_with = "lenient::string")]
     pub short_description: String,
     /// Unique identifier.
+    #[serde(deserialize_with = "lenient::string")]
     pub sku: String,
     /// Current product price.
+    #[serde(deserialize_with = "lenient::string")]
     pub price: String,
     /// Product regular price.
+    #[serde(deserialize_with = "lenient::string")]
     pub regular_price: String,
     /// Product sale price.
+    #[serde(deserialize_with = "lenient::string")]
     pub sale_price: String,
     /// Start date of sale price, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_from: Option<NaiveDateTime>,
     /// Start date of sale price, as GMT.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_from_gmt: Option<NaiveDateTime>,
     /// End date of sale price, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_to: Option<NaiveDateTime>,
     /// End date of sale price, as GMT.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_on_sale_to_gmt: Option<NaiveDateTime>,
     /// Price formatted in HTML.
+    #[serde(deserialize_with = "lenient::string")]
     pub price_html: String,
     /// Shows if the product is on sale.
+    #[serde(deserialize_with = "lenient::bool")]
     pub on_sale: bool,
     /// Shows if the product can be bought.
+    #[serde(deserialize_with = "lenient::bool")]
     pub purchasable: bool,
     /// Amount of sales.
+    #[serde(deserialize_with = "lenient::number")]
     pub total_sales: i32,
     /// If the product is virtual. Default is false.
     #[serde(rename = "virtual")]
+    #[serde(deserialize_with = "lenient::bool")]
     pub is_virtual: bool,
     /// If the product is downloadable. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub downloadable: bool,
     /// List of downloadable files. See Product - Downloads properties
+    #[serde(deserialize_with = "lenient::vec")]
     pub downloads: Vec<Download>,
     /// Number of times downloadable files can be downloaded after purchase. Default is -1.
+    #[serde(deserialize_with = "lenient::number")]
     pub download_limit: i32,
     /// Number of days until access to downloadable files expires. Default is -1.
+    #[serde(deserialize_with = "lenient::number")]
     pub download_expiry: i32,
     /// Product external URL. Only for external products.
+    #[serde(deserialize_with = "lenient::string")]
     pub external_url: String,
     /// Product external button text. Only for external products.
+    #[serde(deserialize_with = "lenient::string")]
     pub button_text: String,
     /// Tax status. Options: taxable, shipping and none. Default is taxable.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub tax_status: TaxStatus,
     /// Tax class.
+    #[serde(deserialize_with = "lenient::string")]
     pub tax_class: String,
     /// Stock management at product level. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub manage_stock: bool,
     /// Stock quantity.
+    #[serde(deserialize_with = "lenient::option_number")]
     pub stock_quantity: Option<i32>,
     /// Controls the stock status of the product. Options: instock, outofstock, onbackorder. Default is instock.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub stock_status: StockStatus,
     /// If managing stock, this controls if backorders are allowed. Options: no, notify and yes. Default is no.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub backorders: BackordersStatus,
     /// Shows if backorders are allowed.
+    #[serde(deserialize_with = "lenient::bool")]
     pub backorders_allowed: bool,
     /// Shows if the product is on backordered.
+    #[serde(deserialize_with = "lenient::bool")]
     pub backordered: bool,
     /// Allow one item to be bought in a single order. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub sold_individually: bool,
     /// Product weight.
+    #[serde(deserialize_with = "lenient::string")]
     pub weight: String,
     /// Product dimensions.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub dimensions: Dimensions,
     /// Shows if the product need to be shipped.
+    #[serde(deserialize_with = "lenient::bool")]
     pub shipping_required: bool,
     /// Shows whether or not the product shipping is taxable.READ-ONLY
+    #[serde(deserialize_with = "lenient::bool")]
     pub shipping_taxable: bool,
     /// Shipping class slug.
+    #[serde(deserialize_with = "lenient::string")]
     pub shipping_class: String,
     /// Shipping class ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub shipping_class_id: i32,
     /// Allow reviews. Default is true.
+    #[serde(deserialize_with = "lenient::bool")]
     pub reviews_allowed: bool,
     /// Reviews average rating.
+    #[serde(deserialize_with = "lenient::string")]
     pub average_rating: String,
     /// Amount of reviews that the product have.
+    #[serde(deserialize_with = "lenient::number")]
     pub rating_count: i32,
     /// List of related products IDs.
+    #[serde(deserialize_with = "lenient::vec")]
     pub related_ids: Vec<i32>,
     /// List of up-sell products IDs.
+    #[serde(deserialize_with = "lenient::vec")]
     pub upsell_ids: Vec<i32>,
     /// List of cross-sell products IDs.
+    #[serde(deserialize_with = "lenient::vec")]
     pub cross_sell_ids: Vec<i32>,
     /// Product parent ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub parent_id: i32,
     /// Optional note to send the customer after purchase.
+    #[serde(deserialize_with = "lenient::string")]
     pub purchase_note: String,
     /// List of categories.
+    #[serde(deserialize_with = "lenient::vec")]
     pub categories: Vec<ProductCategory>,
     /// List of tags.
+    #[serde(deserialize_with = "lenient::vec")]
     pub tags: Vec<ProductTag>,
     /// List of images.
+    #[serde(deserialize_with = "lenient::vec")]
     pub images: Vec<ProductImage>,
     /// List of attributes.
+    #[serde(deserialize_with = "lenient::vec")]
     pub attributes: Vec<ProductAttribute>,
     /// Defaults variation attributes.
+    #[serde(deserialize_with = "lenient::vec")]
     pub default_attributes: Vec<ProductDefaultAttribute>,
     /// List of variations IDs.
+    #[serde(deserialize_with = "lenient::vec")]
     pub variations: Vec<i32>,
     /// List of grouped products ID.
+    #[serde(deserialize_with = "lenient::vec")]
     pub grouped_products: Vec<i32>,
     /// Menu order, used to custom sort products.
+    #[serde(deserialize_with = "lenient::number")]
     pub menu_order: i32,
     /// Meta data.
-    pub meta_data: Vec<MetaData>,
+    pub meta_data: MetaBag,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for Product {
     fn endpoint() -> String {
@@ -154,6 +224,48 @@ impl Product {
     pub fn builder() -> ProductModifyBuilder {
         ProductModify::builder()
     }
+    /// Attributes used for variations, to generate them with [`ApiClient::generate_variations`](crate::ApiClient::generate_variations).
+    pub fn variation_attributes(&self) -> Vec<VariationAttribute> {
+        self.attributes
+            .iter()
+            .filter(|a| a.variation)
+            .map(VariationAttribute::from)
+            .collect()
+    }
+    /// Variation matching the selected attributes, see [`ProductVariation::match_variation`].
+    /// Attributes missing from the selection take the default value of the product.
+    pub fn match_variation<'a>(
+        &self,
+        variations: &'a [ProductVariation],
+        attributes: &[(&str, &str)],
+    ) -> Option<&'a ProductVariation> {
+        let mut selection = attributes.to_vec();
+        for default in &self.default_attributes {
+            if !attributes
+                .iter()
+                .any(|(name, _)| same_attribute_name(name, &default.name))
+            {
+                selection.push((default.name.as_str(), default.option.as_str()));
+            }
+        }
+        ProductVariation::match_variation(variations, &selection)
+    }
+    /// The date the product was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
+    /// The date the product was last modified, as UTC.
+    pub fn date_modified_utc(&self) -> DateTime<Utc> {
+        self.date_modified_gmt.and_utc()
+    }
+    /// Start date of sale price, as UTC.
+    pub fn date_on_sale_from_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_on_sale_from_gmt.map(|d| d.and_utc())
+    }
+    /// End date of sale price, as UTC.
+    pub fn date_on_sale_to_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_on_sale_to_gmt.map(|d| d.and_utc())
+    }
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -163,6 +275,9 @@ pub enum ProductType {
     Grouped,
     External,
     Variable,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -172,6 +287,9 @@ pub enum ProductStatus {
     Private,
     #[default]
     Publish,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -181,6 +299,9 @@ pub enum CatalogVisibility {
     Catalog,
     Search,
     Hidden,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -189,6 +310,9 @@ pub enum TaxStatus {
     Taxable,
     Shipping,
     None,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -197,6 +321,9 @@ pub enum StockStatus {
     Instock,
     Outofstock,
     Onbackorder,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -205,83 +332,153 @@ pub enum BackordersStatus {
     No,
     Notify,
     Yes,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Download {
     /// File ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub id: String,
     /// File name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// File URL.
+    #[serde(deserialize_with = "lenient::string")]
     pub file: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Dimensions {
     /// Product length.
+    #[serde(deserialize_with = "lenient::string")]
     pub length: String,
     /// Product width.
+    #[serde(deserialize_with = "lenient::string")]
     pub width: String,
     /// Product height.
+    #[serde(deserialize_with = "lenient::string")]
     pub height: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ProductCategory {
     /// Category ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Category name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Category slug.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ProductTag {
     /// Tag ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Tag name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Tag slug.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ProductImage {
     /// Image ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// The date the image was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the image was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// The date the image was last modified, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified: NaiveDateTime,
     /// The date the image was last modified, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_modified_gmt: NaiveDateTime,
     /// Image URL.
+    #[serde(deserialize_with = "lenient::string")]
     pub src: String,
     /// Image name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Image alternative text.
+    #[serde(deserialize_with = "lenient::string")]
     pub alt: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl ProductImage {
+    /// The date the image was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
+    /// The date the image was last modified, as UTC.
+    pub fn date_modified_utc(&self) -> DateTime<Utc> {
+        self.date_modified_gmt.and_utc()
+    }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ProductAttribute {
     /// Attribute ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Attribute name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Attribute position.
+    #[serde(deserialize_with = "lenient::number")]
     pub position: i32,
     /// Define if the attribute is visible on the "Additional information" tab in the product's page. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub visible: bool,
     /// Define if the attribute can be used as variation. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub variation: bool,
     /// List of available term names of the attribute.
+    #[serde(deserialize_with = "lenient::vec")]
     pub options: Vec<String>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ProductDefaultAttribute {
     /// Attribute ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Attribute name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Selected attribute term name.
+    #[serde(deserialize_with = "lenient::string")]
     pub option: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
@@ -1,34 +1,51 @@
-use crate::controllers::refunds::{NoAmount, NoItems, RefundCreate, RefundCreateBuilder};
+use crate::controllers::refunds::{
+    NoAmount, NoItems, OrderRefundBuilder, RefundCreate, RefundCreateBuilder,
+};
+use crate::Order;
 
+use super::lenient;
 use super::MetaData;
 use crate::controllers::Entity;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Refund {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// The date the order refund was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the order refund was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// Total refund amount. Optional. If this parameter is provided, it will take precedence over line item totals, even when total of line items does not matches with this amount.
+    #[serde(deserialize_with = "lenient::option")]
     pub amount: Option<String>,
     /// Reason for refund.
+    #[serde(deserialize_with = "lenient::string")]
     pub reason: String,
     /// User ID of user who created the refund.    
+    #[serde(deserialize_with = "lenient::number")]
     pub refunded_by: i32,
     /// If the payment was refunded via the API. See api_refund.    
+    #[serde(deserialize_with = "lenient::bool")]
     pub refunded_payment: bool,
     /// Meta data.    
+    #[serde(deserialize_with = "lenient::vec")]
     pub meta_data: Vec<MetaData>,
     /// Line items data.
+    #[serde(deserialize_with = "lenient::vec")]
     pub line_items: Vec<OrderRefundLineItem>,
     // When true, the payment gateway API is used to generate the refund. Default is true.
     // pub api_refund:	bool,
     // When true, the selected line items are restocked Default is true.
     // pub api_restock: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for Refund {
     fn endpoint() -> String {
@@ -39,52 +56,113 @@ impl Entity for Refund {
         format!("orders/{parent_id}/refunds/")
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderRefundLineItem {
     /// Item ID
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Product name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Product ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub product_id: i32,
     /// Variation ID, if applicable.    
+    #[serde(deserialize_with = "lenient::option_number")]
     pub variation_id: Option<i32>,
     /// Quantity ordered.
+    #[serde(deserialize_with = "lenient::number")]
     pub quantity: i32,
     /// Tax class of product.
+    #[serde(deserialize_with = "lenient::number")]
     pub tax_class: i32,
     /// Line subtotal (before discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub subtotal: String,
     /// Line subtotal tax (before discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub subtotal_tax: String,
     /// Line total (after discounts).    
+    #[serde(deserialize_with = "lenient::string")]
     pub total: String,
     /// Line total tax (after discounts).
+    #[serde(deserialize_with = "lenient::string")]
     pub total_tax: String,
     /// Line taxes.    
+    #[serde(deserialize_with = "lenient::vec")]
     pub taxes: Vec<OrderRefundLineItemTaxesProperties>,
     /// Meta data.
+    #[serde(deserialize_with = "lenient::vec")]
     pub meta_data: Vec<MetaData>,
     /// Product SKU.    
+    #[serde(deserialize_with = "lenient::string")]
     pub sku: String,
     /// Product price.    
+    #[serde(deserialize_with = "lenient::string")]
     pub price: String,
     // The amount to refund for this line item, excluding taxes.
     // pub refund_total: f64,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct OrderRefundLineItemTaxesProperties {
     /// Tax rate ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Tax total.
+    #[serde(deserialize_with = "lenient::string")]
     pub total: String,
     /// Tax subtotal.
+    #[serde(deserialize_with = "lenient::string")]
     pub subtotal: String,
     // The amount to refund for this tax.
     // pub refund_total: f64,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Refund {
     pub fn create() -> RefundCreateBuilder<NoAmount, NoItems> {
         RefundCreate::builder()
     }
+    /// Refund of order lines, with totals and taxes taken from the order.
+    /// The refund total is capped by the order refunds, pass the full refunds with
+    /// `previous_refunds` to cap each line too.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use rust_woocommerce::{ApiClient, Config, Order, Refund};
+    /// use tracing::info;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     tracing_subscriber::fmt::init();
+    ///     let config = Config::new("woo.toml")?;
+    ///     let client = ApiClient::new(&config)?;
+    ///     let order = client.retrieve::<Order>(12345).await?;
+    ///     let previous = client.order_refunds(order.id).await?;
+    ///     let refund = Refund::for_order(&order)
+    ///         .previous_refunds(&previous)
+    ///         .item(order.line_items[0].id, 1)
+    ///         .shipping(order.shipping_lines[0].id)
+    ///         .reason("Damaged in transit")
+    ///         .build()?;
+    ///     let created: Refund = client.create_subentity(order.id, refund).await?;
+    ///     info!("Refunded {:?}", created.amount);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn for_order(order: &Order) -> OrderRefundBuilder {
+        OrderRefundBuilder::new(order)
+    }
+    /// The date the order refund was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
 }
@@ -1,11 +1,18 @@
+use super::lenient;
 use crate::controllers::Entity;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Report {
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for Report {
     fn endpoint() -> String {
@@ -18,31 +25,65 @@ impl Entity for Report {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct SaleReport {
     /// Gross sales in the period.
+    #[serde(deserialize_with = "lenient::string")]
     pub total_sales: String,
     /// Net sales in the period.
+    #[serde(deserialize_with = "lenient::string")]
     pub net_sales: String,
     /// Average net daily sales.
+    #[serde(deserialize_with = "lenient::string")]
     pub average_sales: String,
     /// Total of orders placed.
+    #[serde(deserialize_with = "lenient::number")]
     pub total_orders: i32,
     /// Total of items purchased.
+    #[serde(deserialize_with = "lenient::number")]
     pub total_items: i32,
     /// Total charged for taxes.
+    #[serde(deserialize_with = "lenient::string")]
     pub total_tax: String,
     /// Total charged for shipping.
+    #[serde(deserialize_with = "lenient::string")]
     pub total_shipping: String,
     /// Total of refunded orders.
+    #[serde(deserialize_with = "lenient::number")]
     pub total_refunds: i32,
     /// Total of coupons used.
+    #[serde(deserialize_with = "lenient::string")]
     pub total_discount: String,
-    /// Group type.
+    /// Group type, `day` or `month` for periods longer than about a month.
+    #[serde(deserialize_with = "lenient::string")]
     pub totals_grouped_by: String,
-    /// Totals.
-    pub totals: std::collections::HashMap<NaiveDate, Total>,
+    /// Totals by day (`2024-01-31`) or month (`2024-01`), see [`SaleReport::totals_by_date`].
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub totals: std::collections::HashMap<String, Total>,
+    #[serde(deserialize_with = "lenient::number")]
     pub total_customers: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl SaleReport {
+    /// Totals sorted by date, months by their first day.
+    pub fn totals_by_date(&self) -> Vec<(NaiveDate, &Total)> {
+        let mut totals = self
+            .totals
+            .iter()
+            .filter_map(|(key, total)| {
+                let date = match self.totals_grouped_by.as_str() {
+                    "month" => NaiveDate::parse_from_str(&format!("{key}-01"), "%Y-%m-%d"),
+                    _ => NaiveDate::parse_from_str(key, "%Y-%m-%d"),
+                };
+                date.ok().map(|date| (date, total))
+            })
+            .collect::<Vec<_>>();
+        totals.sort_by_key(|(date, _)| *date);
+        totals
+    }
 }
 impl Entity for SaleReport {
     fn endpoint() -> String {
@@ -54,24 +95,42 @@ impl Entity for SaleReport {
         String::new()
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Total {
+    #[serde(deserialize_with = "lenient::string")]
     pub sales: String,
+    #[serde(deserialize_with = "lenient::number")]
     pub orders: i32,
+    #[serde(deserialize_with = "lenient::number")]
     pub items: i32,
+    #[serde(deserialize_with = "lenient::string")]
     pub tax: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub shipping: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub discount: String,
+    #[serde(deserialize_with = "lenient::number")]
     pub customers: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct TopSellersReport {
     /// Product title.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Product ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub product_id: i32,
     /// Total number of purchases.
+    #[serde(deserialize_with = "lenient::number")]
     pub quantity: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Entity for TopSellersReport {
@@ -85,19 +144,117 @@ impl Entity for TopSellersReport {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ReportOrdersTotals {
+/// Total of a totals report, e.g. the number of orders with a status.
+/// Replaces `ReportOrdersTotals`: orders totals are retrieved with
+/// [`ApiClient::report_totals`](crate::ApiClient::report_totals) and [`ReportTotalsKind::Orders`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ReportTotals {
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
+    #[serde(deserialize_with = "lenient::number")]
     pub total: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-impl Entity for ReportOrdersTotals {
-    fn endpoint() -> String {
-        String::from("reports/orders/totals/")
+/// Totals report to retrieve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportTotalsKind {
+    /// Coupons by discount type.
+    Coupons,
+    /// Customers, paying or not.
+    Customers,
+    /// Orders by status.
+    Orders,
+    /// Products by type.
+    Products,
+    /// Reviews by rating.
+    Reviews,
+}
+impl ReportTotalsKind {
+    pub(crate) fn endpoint(&self) -> String {
+        let report = match self {
+            ReportTotalsKind::Coupons => "coupons",
+            ReportTotalsKind::Customers => "customers",
+            ReportTotalsKind::Orders => "orders",
+            ReportTotalsKind::Products => "products",
+            ReportTotalsKind::Reviews => "reviews",
+        };
+        format!("reports/{report}/totals/")
+    }
+}
+/// Period of the sales and top sellers reports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportPeriod {
+    Week,
+    Month,
+    LastMonth,
+    Year,
+    /// Custom range of dates, both included.
+    DateRange(NaiveDate, NaiveDate),
+}
+impl ReportPeriod {
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let period = |period: &str| vec![("period", period.to_string())];
+        match self {
+            ReportPeriod::Week => period("week"),
+            ReportPeriod::Month => period("month"),
+            ReportPeriod::LastMonth => period("last_month"),
+            ReportPeriod::Year => period("year"),
+            ReportPeriod::DateRange(min, max) => vec![
+                ("date_min", min.format("%Y-%m-%d").to_string()),
+                ("date_max", max.format("%Y-%m-%d").to_string()),
+            ],
+        }
     }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn child_endpoint(parent_id: i32) -> String {
-        let _ = parent_id;
-        String::new()
+    #[test]
+    fn totals_grouped_by_month() {
+        let report: SaleReport = serde_json::from_value(serde_json::json!({
+            "total_sales": "300.00",
+            "total_orders": 3,
+            "totals_grouped_by": "month",
+            "totals": {
+                "2024-03": {"sales": "50.00", "orders": 1},
+                "2024-01": {"sales": "250.00", "orders": 2},
+                "2024-02": {"sales": "0.00", "orders": 0}
+            }
+        }))
+        .unwrap();
+        assert_eq!(report.totals["2024-01"].orders, 2);
+        let months = report
+            .totals_by_date()
+            .into_iter()
+            .map(|(date, total)| (date.to_string(), total.orders))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            months,
+            [
+                (String::from("2024-01-01"), 2),
+                (String::from("2024-02-01"), 0),
+                (String::from("2024-03-01"), 1)
+            ]
+        );
+    }
+    #[test]
+    fn totals_grouped_by_day() {
+        let report: SaleReport = serde_json::from_value(serde_json::json!({
+            "totals_grouped_by": "day",
+            "totals": {
+                "2024-01-02": {"sales": "10.00", "orders": 1},
+                "2024-01-01": {"sales": "0.00", "orders": 0}
+            }
+        }))
+        .unwrap();
+        let days = report.totals_by_date();
+        assert_eq!(days[0].0, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(days[1].1.orders, 1);
     }
 }
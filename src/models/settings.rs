@@ -1,18 +1,33 @@
-use crate::controllers::Entity;
+use std::fmt::Display;
+
+use super::lenient;
+use super::payment_gateways::SettingType;
+use crate::controllers::{settings::SettingOptionUpdate, Entity};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct SettingGroup {
     /// A unique identifier that can be used to link settings together.
+    #[serde(deserialize_with = "lenient::string")]
     pub id: String,
     /// A human-readable label for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
     pub label: String,
     /// A human-readable description for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// ID of parent grouping.
+    #[serde(deserialize_with = "lenient::string")]
     pub parent_id: String,
     /// IDs for settings subgroups.
+    #[serde(deserialize_with = "lenient::vec")]
     pub sub_groups: Vec<String>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for SettingGroup {
     fn endpoint() -> String {
@@ -24,3 +39,202 @@ impl Entity for SettingGroup {
         String::new()
     }
 }
+/// Setting option of a group, retrieved with
+/// [`ApiClient::list_setting_options`](crate::ApiClient::list_setting_options) as options
+/// only exist within their group.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SettingOption {
+    /// A unique identifier for the setting.
+    #[serde(deserialize_with = "lenient::string")]
+    pub id: String,
+    /// A human-readable label for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
+    pub label: String,
+    /// A human-readable description for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
+    pub description: String,
+    /// Type of setting.
+    #[serde(rename = "type")]
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub setting_type: SettingType,
+    /// Default value for the setting.
+    pub default: serde_json::Value,
+    /// Array of options (key value pairs) for inputs such as select, multiselect, and radio buttons.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub options: serde_json::Map<String, serde_json::Value>,
+    /// Additional help text shown to the user about the setting.
+    #[serde(deserialize_with = "lenient::string")]
+    pub tip: String,
+    /// Setting value.
+    pub value: serde_json::Value,
+    /// An identifier for the group this setting belongs to.
+    #[serde(deserialize_with = "lenient::string")]
+    pub group_id: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl SettingOption {
+    /// Value of the setting as a string, e.g. `"yes"` for checkboxes or `"USD"` for the currency.
+    pub fn value_str(&self) -> Option<&str> {
+        self.value.as_str()
+    }
+    /// Value of a checkbox setting.
+    pub fn is_enabled(&self) -> bool {
+        self.value.as_str() == Some("yes")
+    }
+    /// New value for the setting with given id, to use with batch update.
+    pub fn update(id: impl Into<String>, value: impl Serialize) -> SettingOptionUpdate {
+        SettingOptionUpdate::new(id, value)
+    }
+}
+/// Store timezone, as configured in WordPress general settings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum StoreTimezone {
+    /// Named timezone, e.g. `Europe/Moscow`.
+    Named(chrono_tz::Tz),
+    /// Manual UTC offset, e.g. `UTC+3`.
+    Offset(FixedOffset),
+}
+impl Default for StoreTimezone {
+    fn default() -> Self {
+        StoreTimezone::Offset(Utc.fix())
+    }
+}
+impl StoreTimezone {
+    /// Parse a timezone string as stored by WordPress: either a named timezone (`Europe/Moscow`)
+    /// or a manual offset (`UTC+3`, `UTC-5:30`, `UTC+5.5`).
+    /// Fails for an empty string, which WordPress stores when a manual offset is used,
+    /// see [`StoreTimezone::from_gmt_offset`].
+    pub fn parse(timezone: &str) -> Result<Self> {
+        let timezone = timezone.trim();
+        if timezone.is_empty() {
+            return Err(anyhow!("Empty timezone"));
+        }
+        if timezone == "UTC" {
+            return Ok(Self::default());
+        }
+        if let Some(offset) = timezone.strip_prefix("UTC") {
+            let (sign, offset) = match (offset.strip_prefix('+'), offset.strip_prefix(['-', '−']))
+            {
+                (Some(rest), _) => (1, rest),
+                (_, Some(rest)) => (-1, rest),
+                _ => return Err(anyhow!("Invalid timezone offset: {timezone}")),
+            };
+            let seconds = match offset.split_once([':', '.']) {
+                Some((hours, minutes)) if offset.contains(':') => {
+                    hours.parse::<i32>()? * 3600 + minutes.parse::<i32>()? * 60
+                }
+                _ => (offset.parse::<f64>()? * 3600.0).round() as i32,
+            };
+            return FixedOffset::east_opt(sign * seconds)
+                .map(Self::Offset)
+                .ok_or(anyhow!("Invalid timezone offset: {timezone}"));
+        }
+        timezone
+            .parse::<chrono_tz::Tz>()
+            .map(Self::Named)
+            .map_err(|e| anyhow!("Invalid timezone {timezone}: {e}"))
+    }
+    /// Manual UTC offset in hours, as in the WordPress `gmt_offset` option, e.g. `5.5`.
+    pub fn from_gmt_offset(hours: f64) -> Result<Self> {
+        let seconds = (hours * 3600.0).round() as i32;
+        FixedOffset::east_opt(seconds)
+            .map(Self::Offset)
+            .ok_or(anyhow!("Invalid timezone offset: {hours} hours"))
+    }
+    /// Build a fixed offset timezone from a pair of site-local and GMT timestamps of the same
+    /// moment, e.g. `date_created` and `date_created_gmt` of any resource.
+    pub fn from_sample(local: NaiveDateTime, gmt: NaiveDateTime) -> Result<Self> {
+        let seconds = (local - gmt).num_seconds() as i32;
+        FixedOffset::east_opt(seconds)
+            .map(Self::Offset)
+            .ok_or(anyhow!("Invalid timezone offset: {seconds} seconds"))
+    }
+    /// Convert a UTC timestamp into the store's local time.
+    pub fn to_local(&self, date: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            StoreTimezone::Named(tz) => date.with_timezone(tz).fixed_offset(),
+            StoreTimezone::Offset(offset) => date.with_timezone(offset),
+        }
+    }
+    /// Convert a store local time into UTC. Returns None for local times skipped by a DST change.
+    pub fn to_utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            StoreTimezone::Named(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|d| d.with_timezone(&Utc)),
+            StoreTimezone::Offset(offset) => offset
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|d| d.with_timezone(&Utc)),
+        }
+    }
+}
+impl Display for StoreTimezone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreTimezone::Named(tz) => write!(f, "{}", tz.name()),
+            StoreTimezone::Offset(offset) => {
+                let seconds = offset.local_minus_utc();
+                let sign = if seconds < 0 { '-' } else { '+' };
+                let seconds = seconds.abs();
+                match seconds % 3600 {
+                    0 => write!(f, "UTC{sign}{}", seconds / 3600),
+                    rest => write!(f, "UTC{sign}{}:{:02}", seconds / 3600, rest / 60),
+                }
+            }
+        }
+    }
+}
+impl TryFrom<String> for StoreTimezone {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+impl From<StoreTimezone> for String {
+    fn from(value: StoreTimezone) -> Self {
+        value.to_string()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_offsets() {
+        let offset = |seconds| StoreTimezone::Offset(FixedOffset::east_opt(seconds).unwrap());
+        assert_eq!(StoreTimezone::parse("UTC+3").unwrap(), offset(3 * 3600));
+        assert_eq!(
+            StoreTimezone::parse("UTC-5:30").unwrap(),
+            offset(-5 * 3600 - 1800)
+        );
+        assert_eq!(
+            StoreTimezone::parse("UTC+5.5").unwrap(),
+            offset(5 * 3600 + 1800)
+        );
+        assert_eq!(StoreTimezone::parse("UTC−3").unwrap(), offset(-3 * 3600));
+        assert_eq!(StoreTimezone::parse("UTC").unwrap(), offset(0));
+        assert_eq!(
+            StoreTimezone::parse("Europe/Moscow").unwrap(),
+            StoreTimezone::Named(chrono_tz::Europe::Moscow)
+        );
+        assert!(StoreTimezone::parse("").is_err());
+        assert!(StoreTimezone::parse("UTC3").is_err());
+        assert!(StoreTimezone::parse("UTC×3").is_err());
+    }
+    #[test]
+    fn gmt_offset() {
+        let timezone = StoreTimezone::from_gmt_offset(-9.5).unwrap();
+        assert_eq!(timezone.to_string(), "UTC-9:30");
+        assert_eq!(
+            StoreTimezone::parse(&timezone.to_string()).unwrap(),
+            timezone
+        );
+    }
+}
@@ -1,14 +1,22 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingMethod {
     /// Method ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub id: String,
     /// Shipping method title.
+    #[serde(deserialize_with = "lenient::string")]
     pub title: String,
     /// Shipping method description.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for ShippingMethod {
     fn endpoint() -> String {
@@ -1,13 +1,20 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingZoneLocation {
     /// Shipping zone location code.
+    #[serde(deserialize_with = "lenient::string")]
     pub code: String,
     /// Shipping zone location type. Options: postcode, state, country and continent. Default is country.
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "lenient::or_default")]
     pub location_type: LocationType,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for ShippingZoneLocation {
     fn endpoint() -> String {
@@ -18,11 +25,15 @@ impl Entity for ShippingZoneLocation {
         format!("shipping/zones/{parent_id}/locations/")
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum LocationType {
     Postcode,
     State,
+    #[default]
     Country,
     Continent,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
@@ -1,25 +1,43 @@
+use std::collections::BTreeMap;
+
+use super::lenient;
+use crate::controllers::shipping_zone_methods::ShippingZoneMethodModify;
 use crate::controllers::Entity;
-use serde::{Deserialize, Serialize};
+use crate::TaxStatus;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingZoneMethod {
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i64,
     /// Shipping method instance ID.
+    #[serde(deserialize_with = "lenient::number")]
     pub instance_id: i32,
     /// Shipping method customer facing title.
+    #[serde(deserialize_with = "lenient::string")]
     pub title: String,
     /// Shipping method sort order.
+    #[serde(deserialize_with = "lenient::number")]
     pub order: i32,
     /// Shipping method enabled status.
+    #[serde(deserialize_with = "lenient::bool")]
     pub enabled: bool,
     /// Shipping method ID.
+    #[serde(deserialize_with = "lenient::string")]
     pub method_id: String,
     /// Shipping method title.
+    #[serde(deserialize_with = "lenient::string")]
     pub method_title: String,
     /// Shipping method description.
+    #[serde(deserialize_with = "lenient::string")]
     pub method_description: String,
     /// Shipping method settings.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub settings: ShippingSettings,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for ShippingZoneMethod {
     fn endpoint() -> String {
@@ -30,36 +48,211 @@ impl Entity for ShippingZoneMethod {
         format!("shipping/zones/{parent_id}/methods/")
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ShippingZoneMethod {
+    /// Add a shipping method to a zone, e.g. `flat_rate`, `free_shipping` or `local_pickup`.
+    pub fn create(method_id: impl Into<String>) -> ShippingZoneMethodModify {
+        ShippingZoneMethodModify::new(method_id)
+    }
+    pub fn update() -> ShippingZoneMethodModify {
+        ShippingZoneMethodModify::default()
+    }
+    /// Settings of the core shipping methods with typed values, raw values for other methods.
+    pub fn typed_settings(&self) -> ShippingMethodConfig {
+        let settings = &self.settings;
+        match self.method_id.as_str() {
+            "flat_rate" => ShippingMethodConfig::FlatRate(FlatRateSettings {
+                title: settings.value("title"),
+                tax_status: settings.parse("tax_status"),
+                cost: settings.value("cost"),
+                class_costs: settings
+                    .extra
+                    .iter()
+                    .filter_map(|(id, setting)| {
+                        id.strip_prefix("class_cost_")
+                            .and_then(|class_id| class_id.parse().ok())
+                            .map(|class_id| (class_id, setting.value.clone()))
+                    })
+                    .collect(),
+                no_class_cost: settings.value("no_class_cost"),
+                calculation_type: settings.parse("type"),
+            }),
+            "free_shipping" => ShippingMethodConfig::FreeShipping(FreeShippingSettings {
+                title: settings.value("title"),
+                requires: settings.parse("requires"),
+                min_amount: settings.value("min_amount"),
+                ignore_discounts: settings.value("ignore_discounts") == "yes",
+            }),
+            "local_pickup" => ShippingMethodConfig::LocalPickup(LocalPickupSettings {
+                title: settings.value("title"),
+                tax_status: settings.parse("tax_status"),
+                cost: settings.value("cost"),
+            }),
+            _ => ShippingMethodConfig::Other(
+                std::iter::once(("title".to_string(), settings.value("title")))
+                    .chain(
+                        settings
+                            .requires
+                            .iter()
+                            .map(|r| ("requires".to_string(), r.value.clone())),
+                    )
+                    .chain(
+                        settings
+                            .extra
+                            .iter()
+                            .map(|(id, setting)| (id.clone(), setting.value.clone())),
+                    )
+                    .collect(),
+            ),
+        }
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingSettings {
+    #[serde(deserialize_with = "lenient::or_default")]
     pub title: ShippingMethodSettings,
+    #[serde(deserialize_with = "lenient::option")]
     pub requires: Option<ShippingMethodSettings>,
+    /// Other settings by setting id, e.g. `cost`, `tax_status` or `class_cost_{id}`.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, ShippingMethodSettings>,
+}
+impl ShippingSettings {
+    /// Any setting by its id.
+    pub fn get(&self, id: &str) -> Option<&ShippingMethodSettings> {
+        match id {
+            "title" => Some(&self.title),
+            "requires" => self.requires.as_ref(),
+            _ => self.extra.get(id),
+        }
+    }
+    /// Value of a setting, empty if the method doesn't have it.
+    pub fn value(&self, id: &str) -> String {
+        self.get(id).map(|s| s.value.clone()).unwrap_or_default()
+    }
+    fn parse<T: DeserializeOwned + Default>(&self, id: &str) -> T {
+        match self.get(id) {
+            Some(setting) if !setting.value.is_empty() => {
+                serde_json::from_value(serde_json::json!(setting.value)).unwrap_or_default()
+            }
+            _ => T::default(),
+        }
+    }
+}
+/// Settings of a shipping method by method id.
+#[derive(Debug, Clone)]
+pub enum ShippingMethodConfig {
+    FlatRate(FlatRateSettings),
+    FreeShipping(FreeShippingSettings),
+    LocalPickup(LocalPickupSettings),
+    /// Setting values of any other method, by setting id.
+    Other(BTreeMap<String, String>),
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FlatRateSettings {
+    /// Method title.
+    pub title: String,
+    /// Tax status, `taxable` or `none`.
+    pub tax_status: TaxStatus,
+    /// Cost formula, e.g. `10 + [qty] * 2` or `[fee percent="10" min_fee="5"]`.
+    pub cost: String,
+    /// Cost formulas by shipping class ID.
+    pub class_costs: BTreeMap<i32, String>,
+    /// Cost formula for products without a shipping class.
+    pub no_class_cost: String,
+    /// Charge shipping for each class or only for the most expensive one.
+    pub calculation_type: FlatRateCalculation,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlatRateCalculation {
+    /// Per class: charge shipping for each shipping class individually.
+    #[default]
+    Class,
+    /// Per order: charge shipping for the most expensive shipping class.
+    Order,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FreeShippingSettings {
+    /// Method title.
+    pub title: String,
+    /// Condition to make free shipping available.
+    pub requires: FreeShippingRequires,
+    /// Minimum order amount, used by `min_amount`, `either` and `both` conditions.
+    pub min_amount: String,
+    /// Check the minimum amount before coupon discounts.
+    pub ignore_discounts: bool,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FreeShippingRequires {
+    /// Always available.
+    #[default]
+    #[serde(rename = "")]
+    Nothing,
+    /// A valid free shipping coupon.
+    Coupon,
+    /// A minimum order amount.
+    MinAmount,
+    /// A minimum order amount or a coupon.
+    Either,
+    /// A minimum order amount and a coupon.
+    Both,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LocalPickupSettings {
+    /// Method title.
+    pub title: String,
+    /// Tax status, `taxable` or `none`.
+    pub tax_status: TaxStatus,
+    /// Pickup cost.
+    pub cost: String,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingMethodSettings {
     /// A unique identifier for the setting.
+    #[serde(deserialize_with = "lenient::string")]
     pub id: String,
     /// A human readable label for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
     pub label: String,
     /// A human readable description for the setting used in interfaces.
+    #[serde(deserialize_with = "lenient::string")]
     pub description: String,
     /// Type of setting. Options: text, email, number, color, password, textarea, select, multiselect, radio, image_width and checkbox.
     #[serde(rename = "type")]
+    #[serde(deserialize_with = "lenient::or_default")]
     pub settings_type: SettingsType,
     /// Setting value.
+    #[serde(deserialize_with = "lenient::string")]
     pub value: String,
     /// Default value for the setting.
     #[serde(rename = "default")]
+    #[serde(deserialize_with = "lenient::string")]
     pub default_value: String,
     /// Additional help text shown to the user about the setting.
+    #[serde(deserialize_with = "lenient::string")]
     pub tip: String,
     /// Placeholder text to be displayed in text inputs.
+    #[serde(deserialize_with = "lenient::string")]
     pub placeholder: String,
+    #[serde(deserialize_with = "lenient::option")]
     pub options: Option<serde_json::Value>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum SettingsType {
+    #[default]
     Text,
     Email,
     Number,
@@ -71,4 +264,7 @@ pub enum SettingsType {
     Radio,
     ImageWidth,
     Checkbox,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
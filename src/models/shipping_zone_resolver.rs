@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use super::{
+    data::Continent,
+    location::postcode_matches,
+    shipping_zone_locations::{LocationType, ShippingZoneLocation},
+    shipping_zone_methods::ShippingZoneMethod,
+    shipping_zones::ShippingZone,
+};
+
+/// Offline resolution of the shipping zone of a destination, the way WooCommerce does it.
+/// Build it with [`ApiClient::shipping_zone_resolver`](crate::ApiClient::shipping_zone_resolver)
+/// or from zones fetched earlier.
+#[derive(Debug, Clone, Default)]
+pub struct ShippingZoneResolver {
+    zones: Vec<ResolverZone>,
+    fallback: Option<ResolvedShippingZone>,
+    continents: BTreeMap<String, String>,
+}
+#[derive(Debug, Clone)]
+struct ResolverZone {
+    zone: ShippingZone,
+    locations: Vec<ShippingZoneLocation>,
+    methods: Vec<ShippingZoneMethod>,
+}
+/// Shipping zone of a destination with its enabled methods.
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedShippingZone {
+    /// Matched zone, zone 0 for locations not covered by the other zones.
+    pub zone: ShippingZone,
+    /// Enabled shipping methods of the zone, by order.
+    pub methods: Vec<ShippingZoneMethod>,
+}
+impl ShippingZoneResolver {
+    pub fn new() -> Self {
+        ShippingZoneResolver::default()
+    }
+    /// Shipping zone with its locations and methods.
+    /// Zone 0 is the "Locations not covered by your other zones" zone, used as fallback.
+    pub fn zone(
+        mut self,
+        zone: ShippingZone,
+        locations: Vec<ShippingZoneLocation>,
+        methods: Vec<ShippingZoneMethod>,
+    ) -> Self {
+        if zone.id == 0 {
+            let _ = self.fallback.insert(ResolvedShippingZone {
+                zone,
+                methods: enabled(methods),
+            });
+            return self;
+        }
+        self.zones.push(ResolverZone {
+            zone,
+            locations,
+            methods,
+        });
+        self.zones.sort_by_key(|z| (z.zone.order, z.zone.id));
+        self
+    }
+    /// Continents with their countries, needed to match continent locations.
+    pub fn continents(mut self, continents: &[Continent]) -> Self {
+        for continent in continents {
+            for country in &continent.countries {
+                self.continents
+                    .insert(country.code.to_uppercase(), continent.code.to_uppercase());
+            }
+        }
+        self
+    }
+    /// Zone of a destination: the first zone by order with a matching country, state or continent,
+    /// and a matching postcode if it has postcodes. Falls back to zone 0 if it was added.
+    pub fn resolve(
+        &self,
+        country: &str,
+        state: &str,
+        postcode: &str,
+    ) -> Option<ResolvedShippingZone> {
+        self.zones
+            .iter()
+            .find(|z| self.zone_matches(z, country, state, postcode))
+            .map(|z| ResolvedShippingZone {
+                zone: z.zone.to_owned(),
+                methods: enabled(z.methods.to_owned()),
+            })
+            .or_else(|| self.fallback.to_owned())
+    }
+    /// Like WooCommerce, a zone without region locations matches any region,
+    /// so a zone with only postcodes matches them in every country.
+    fn zone_matches(
+        &self,
+        zone: &ResolverZone,
+        country: &str,
+        state: &str,
+        postcode: &str,
+    ) -> bool {
+        let country = country.trim().to_uppercase();
+        let state_code = format!("{country}:{}", state.trim().to_uppercase());
+        let continent = self.continents.get(&country);
+        let (postcodes, regions): (Vec<_>, Vec<_>) = zone
+            .locations
+            .iter()
+            .partition(|l| matches!(l.location_type, LocationType::Postcode));
+        let region_matches = regions.is_empty()
+            || regions.iter().any(|l| {
+                let code = l.code.trim().to_uppercase();
+                match &l.location_type {
+                    LocationType::Country => code == country,
+                    LocationType::State => code == state_code,
+                    LocationType::Continent => continent == Some(&code),
+                    _ => false,
+                }
+            });
+        let postcode_matches = postcodes.is_empty()
+            || postcodes
+                .iter()
+                .any(|l| postcode_matches(&l.code, postcode));
+        region_matches && postcode_matches
+    }
+}
+fn enabled(mut methods: Vec<ShippingZoneMethod>) -> Vec<ShippingZoneMethod> {
+    methods.retain(|m| m.enabled);
+    methods.sort_by_key(|m| m.order);
+    methods
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(id: i32, order: i32) -> ShippingZone {
+        serde_json::from_value(
+            serde_json::json!({ "id": id, "name": id.to_string(), "order": order }),
+        )
+        .unwrap()
+    }
+    fn locations(locations: &[(&str, &str)]) -> Vec<ShippingZoneLocation> {
+        locations
+            .iter()
+            .map(|(location_type, code)| {
+                serde_json::from_value(serde_json::json!({ "code": code, "type": location_type }))
+                    .unwrap()
+            })
+            .collect()
+    }
+    fn method(instance_id: i32, order: i32, enabled: bool) -> ShippingZoneMethod {
+        serde_json::from_value(serde_json::json!({
+            "instance_id": instance_id,
+            "order": order,
+            "enabled": enabled,
+            "method_id": "flat_rate",
+        }))
+        .unwrap()
+    }
+    fn resolved_id(
+        resolver: &ShippingZoneResolver,
+        country: &str,
+        state: &str,
+        postcode: &str,
+    ) -> Option<i32> {
+        resolver
+            .resolve(country, state, postcode)
+            .map(|z| z.zone.id)
+    }
+    #[test]
+    fn first_match_by_zone_order() {
+        let resolver = ShippingZoneResolver::new()
+            .zone(zone(1, 2), locations(&[("country", "US")]), vec![])
+            .zone(zone(2, 1), locations(&[("state", "US:CA")]), vec![])
+            .zone(zone(3, 0), locations(&[("country", "DE")]), vec![]);
+        assert_eq!(resolved_id(&resolver, "us", "ca", "90210"), Some(2));
+        assert_eq!(resolved_id(&resolver, "US", "NY", "10001"), Some(1));
+        assert_eq!(resolved_id(&resolver, "FR", "", "75001"), None);
+    }
+    #[test]
+    fn postcode_only_zone_matches_any_region() {
+        let resolver = ShippingZoneResolver::new()
+            .zone(zone(1, 0), locations(&[("postcode", "902*")]), vec![])
+            .zone(
+                zone(2, 1),
+                locations(&[("country", "US"), ("postcode", "10001...10099")]),
+                vec![],
+            )
+            .zone(zone(3, 2), locations(&[("country", "US")]), vec![]);
+        assert_eq!(resolved_id(&resolver, "US", "CA", "90210"), Some(1));
+        assert_eq!(resolved_id(&resolver, "CA", "ON", "902 10"), Some(1));
+        assert_eq!(resolved_id(&resolver, "US", "NY", "10050"), Some(2));
+        assert_eq!(resolved_id(&resolver, "DE", "", "10050"), None);
+        assert_eq!(resolved_id(&resolver, "US", "NY", "10100"), Some(3));
+    }
+    #[test]
+    fn continents() {
+        let europe: Continent = serde_json::from_value(serde_json::json!({
+            "code": "EU",
+            "countries": [{"code": "DE"}, {"code": "FR"}]
+        }))
+        .unwrap();
+        let resolver =
+            ShippingZoneResolver::new().zone(zone(1, 0), locations(&[("continent", "EU")]), vec![]);
+        assert_eq!(resolved_id(&resolver, "FR", "", ""), None);
+        let resolver = resolver.continents(&[europe]);
+        assert_eq!(resolved_id(&resolver, "FR", "", ""), Some(1));
+        assert_eq!(resolved_id(&resolver, "US", "", ""), None);
+    }
+    #[test]
+    fn fallback_and_enabled_methods() {
+        let resolver = ShippingZoneResolver::new()
+            .zone(
+                zone(1, 0),
+                locations(&[("country", "US")]),
+                vec![
+                    method(11, 2, true),
+                    method(12, 0, false),
+                    method(13, 1, true),
+                ],
+            )
+            .zone(
+                zone(0, 0),
+                vec![],
+                vec![method(1, 0, true), method(2, 1, false)],
+            );
+        let us = resolver.resolve("US", "", "").unwrap();
+        let methods = us.methods.iter().map(|m| m.instance_id).collect::<Vec<_>>();
+        assert_eq!(methods, [13, 11]);
+        let fallback = resolver.resolve("DE", "", "").unwrap();
+        assert_eq!(fallback.zone.id, 0);
+        assert_eq!(fallback.methods.len(), 1);
+    }
+}
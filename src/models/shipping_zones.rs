@@ -1,15 +1,23 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::shipping_zones::{ShippingZoneCreate, ShippingZoneUpdate};
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ShippingZone {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Shipping zone name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Shipping zone order.
+    #[serde(deserialize_with = "lenient::number")]
     pub order: i32,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for ShippingZone {
     fn endpoint() -> String {
@@ -0,0 +1,275 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{data::CurrencyISO, settings::SettingOption, settings::StoreTimezone};
+
+/// Store wide settings from the `general`, `products` and `tax` setting groups.
+/// Serializable, so it can be cached between runs instead of being fetched every time.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StoreSettings {
+    /// Store address.
+    pub address: StoreAddress,
+    /// Store currency.
+    pub currency: CurrencyISO,
+    /// Currency symbol, e.g. `$`.
+    pub currency_symbol: String,
+    /// Position of the currency symbol.
+    pub currency_position: CurrencyPosition,
+    /// Thousand separator of displayed prices.
+    pub thousand_separator: String,
+    /// Decimal separator of displayed prices.
+    pub decimal_separator: String,
+    /// Number of decimals of displayed prices.
+    pub price_decimals: u32,
+    /// Unit product weights are defined in.
+    pub weight_unit: WeightUnit,
+    /// Unit product dimensions are defined in.
+    pub dimension_unit: DimensionUnit,
+    /// Stock management is enabled.
+    pub manage_stock: bool,
+    /// Coupons are enabled.
+    pub coupons_enabled: bool,
+    /// Coupons are applied one after another.
+    pub calc_discounts_sequentially: bool,
+    /// Taxes are enabled.
+    pub calc_taxes: bool,
+    /// Prices are entered with tax.
+    pub prices_include_tax: bool,
+    /// Address used to calculate tax.
+    pub tax_based_on: TaxBasedOn,
+    /// Tax class of shipping, `inherit` to use the tax class of the cart items.
+    pub shipping_tax_class: String,
+    /// Tax is rounded at subtotal level instead of per line.
+    pub tax_round_at_subtotal: bool,
+    /// Additional tax classes, besides the standard one.
+    pub tax_classes: Vec<String>,
+    /// Prices in the shop are displayed with or without tax.
+    pub tax_display_shop: TaxDisplay,
+    /// Prices in the cart are displayed with or without tax.
+    pub tax_display_cart: TaxDisplay,
+    /// Store timezone, None for settings read with [`StoreSettings::from_options`] only.
+    pub timezone: Option<StoreTimezone>,
+}
+impl StoreSettings {
+    /// Read the settings from the options of the `general`, `products` and `tax` groups.
+    /// Missing options keep their default values.
+    pub fn from_options(options: &[SettingOption]) -> Self {
+        let value = |id: &str| {
+            options
+                .iter()
+                .find(|o| o.id == id)
+                .and_then(|o| o.value.as_str())
+                .unwrap_or_default()
+                .to_owned()
+        };
+        let flag = |id: &str| value(id) == "yes";
+        let default_country = value("woocommerce_default_country");
+        let (country, state) = match default_country.split_once(':') {
+            Some((country, state)) => (country.to_owned(), state.to_owned()),
+            None => (default_country, String::new()),
+        };
+        StoreSettings {
+            address: StoreAddress {
+                address_1: value("woocommerce_store_address"),
+                address_2: value("woocommerce_store_address_2"),
+                city: value("woocommerce_store_city"),
+                postcode: value("woocommerce_store_postcode"),
+                country,
+                state,
+            },
+            currency: parse(value("woocommerce_currency")),
+            currency_symbol: String::new(),
+            currency_position: parse(value("woocommerce_currency_pos")),
+            thousand_separator: value("woocommerce_price_thousand_sep"),
+            decimal_separator: value("woocommerce_price_decimal_sep"),
+            price_decimals: value("woocommerce_price_num_decimals").parse().unwrap_or(2),
+            weight_unit: parse(value("woocommerce_weight_unit")),
+            dimension_unit: parse(value("woocommerce_dimension_unit")),
+            manage_stock: flag("woocommerce_manage_stock"),
+            coupons_enabled: flag("woocommerce_enable_coupons"),
+            calc_discounts_sequentially: flag("woocommerce_calc_discounts_sequentially"),
+            calc_taxes: flag("woocommerce_calc_taxes"),
+            prices_include_tax: flag("woocommerce_prices_include_tax"),
+            tax_based_on: parse(value("woocommerce_tax_based_on")),
+            shipping_tax_class: value("woocommerce_shipping_tax_class"),
+            tax_round_at_subtotal: flag("woocommerce_tax_round_at_subtotal"),
+            tax_classes: value("woocommerce_tax_classes")
+                .lines()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(String::from)
+                .collect(),
+            tax_display_shop: parse(value("woocommerce_tax_display_shop")),
+            tax_display_cart: parse(value("woocommerce_tax_display_cart")),
+            timezone: None,
+        }
+    }
+    /// Format an amount the way the store displays prices, e.g. `$1,234.50`.
+    pub fn format_price(&self, amount: f64) -> String {
+        let sign = if amount < 0.0 { "-" } else { "" };
+        let number = self.format_number(amount.abs());
+        let symbol = &self.currency_symbol;
+        match self.currency_position {
+            CurrencyPosition::Right => format!("{sign}{number}{symbol}"),
+            CurrencyPosition::LeftSpace => format!("{sign}{symbol} {number}"),
+            CurrencyPosition::RightSpace => format!("{sign}{number} {symbol}"),
+            _ => format!("{sign}{symbol}{number}"),
+        }
+    }
+    /// Format a number with the store separators and number of decimals, e.g. `1,234.50`.
+    pub fn format_number(&self, amount: f64) -> String {
+        let formatted = format!("{:.*}", self.price_decimals as usize, amount);
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer.to_owned(), Some(fraction.to_owned())),
+            None => (formatted, None),
+        };
+        let (sign, digits) = match integer.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", integer.as_str()),
+        };
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push_str(&self.thousand_separator);
+            }
+            grouped.push(c);
+        }
+        match fraction {
+            Some(fraction) => format!("{sign}{grouped}{}{fraction}", self.decimal_separator),
+            None => format!("{sign}{grouped}"),
+        }
+    }
+    /// Convert a weight in the store unit into another unit.
+    pub fn weight_in(&self, weight: f64, unit: &WeightUnit) -> f64 {
+        self.weight_unit.convert(weight, unit)
+    }
+    /// Convert a dimension in the store unit into another unit.
+    pub fn dimension_in(&self, dimension: f64, unit: &DimensionUnit) -> f64 {
+        self.dimension_unit.convert(dimension, unit)
+    }
+    /// Convert a UTC timestamp into the store's local time. UTC is used if the timezone is unknown.
+    pub fn to_local(&self, date: DateTime<Utc>) -> DateTime<FixedOffset> {
+        self.timezone.unwrap_or_default().to_local(date)
+    }
+}
+/// Missing options fall back to the default value.
+fn parse<T: DeserializeOwned + Default>(value: String) -> T {
+    if value.is_empty() {
+        return T::default();
+    }
+    serde_json::from_value(serde_json::Value::String(value)).unwrap_or_default()
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StoreAddress {
+    /// Address line 1.
+    pub address_1: String,
+    /// Address line 2.
+    pub address_2: String,
+    /// City name.
+    pub city: String,
+    /// Postal code.
+    pub postcode: String,
+    /// ISO code of the country.
+    pub country: String,
+    /// ISO code or name of the state, province or district.
+    pub state: String,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrencyPosition {
+    #[default]
+    Left,
+    Right,
+    LeftSpace,
+    RightSpace,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeightUnit {
+    #[default]
+    Kg,
+    G,
+    Lbs,
+    Oz,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+impl WeightUnit {
+    /// Kilograms in one unit, None for unknown units.
+    pub fn in_kg(&self) -> Option<f64> {
+        match self {
+            WeightUnit::Kg => Some(1.0),
+            WeightUnit::G => Some(0.001),
+            WeightUnit::Lbs => Some(0.453_592_37),
+            WeightUnit::Oz => Some(0.028_349_523_125),
+            WeightUnit::Other(_) => None,
+        }
+    }
+    /// Convert a weight into another unit. Weights in unknown units are returned as is.
+    pub fn convert(&self, weight: f64, to: &WeightUnit) -> f64 {
+        match (self.in_kg(), to.in_kg()) {
+            (Some(from), Some(to)) => weight * from / to,
+            _ => weight,
+        }
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DimensionUnit {
+    M,
+    #[default]
+    Cm,
+    Mm,
+    In,
+    Yd,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+impl DimensionUnit {
+    /// Meters in one unit, None for unknown units.
+    pub fn in_m(&self) -> Option<f64> {
+        match self {
+            DimensionUnit::M => Some(1.0),
+            DimensionUnit::Cm => Some(0.01),
+            DimensionUnit::Mm => Some(0.001),
+            DimensionUnit::In => Some(0.0254),
+            DimensionUnit::Yd => Some(0.9144),
+            DimensionUnit::Other(_) => None,
+        }
+    }
+    /// Convert a dimension into another unit. Dimensions in unknown units are returned as is.
+    pub fn convert(&self, dimension: f64, to: &DimensionUnit) -> f64 {
+        match (self.in_m(), to.in_m()) {
+            (Some(from), Some(to)) => dimension * from / to,
+            _ => dimension,
+        }
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxBasedOn {
+    #[default]
+    Shipping,
+    Billing,
+    Base,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxDisplay {
+    #[default]
+    Excl,
+    Incl,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
+}
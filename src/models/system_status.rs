@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+
+use super::lenient;
+use crate::controllers::Entity;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SystemStatus {
+    /// Environment.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub environment: Environment,
+    /// Database.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub database: Database,
+    /// Active plugins.
+    #[serde(deserialize_with = "lenient::vec")]
+    pub active_plugins: Vec<Plugin>,
+    /// Inactive plugins.
+    #[serde(deserialize_with = "lenient::vec")]
+    pub inactive_plugins: Vec<Plugin>,
+    /// Drop-ins and must-use plugins.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub dropins_mu_plugins: DropinsMuPlugins,
+    /// Theme.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub theme: Theme,
+    /// Settings.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub settings: SystemSettings,
+    /// Security.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub security: Security,
+    /// WooCommerce pages.
+    #[serde(deserialize_with = "lenient::vec")]
+    pub pages: Vec<Page>,
+    /// Number of posts by post type.
+    #[serde(deserialize_with = "lenient::vec")]
+    pub post_type_counts: Vec<PostTypeCount>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl Entity for SystemStatus {
+    fn endpoint() -> String {
+        String::from("system_status")
+    }
+
+    fn child_endpoint(parent_id: i32) -> String {
+        let _ = parent_id;
+        String::new()
+    }
+}
+impl SystemStatus {
+    /// Active plugin by its slug, e.g. `woocommerce-subscriptions`.
+    pub fn active_plugin(&self, slug: &str) -> Option<&Plugin> {
+        self.active_plugins
+            .iter()
+            .find(|p| p.plugin.split('/').next() == Some(slug))
+    }
+    /// Active plugins with a newer version available.
+    pub fn outdated_plugins(&self) -> Vec<&Plugin> {
+        self.active_plugins
+            .iter()
+            .filter(|p| !p.version_latest.is_empty() && p.version_latest != p.version)
+            .collect()
+    }
+    /// WooCommerce pages that are not set, missing or not visible.
+    pub fn broken_pages(&self) -> Vec<&Page> {
+        self.pages
+            .iter()
+            .filter(|p| !p.page_set || !p.page_exists || !p.page_visible)
+            .collect()
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Environment {
+    /// Home URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub home_url: String,
+    /// Site URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub site_url: String,
+    /// WooCommerce version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub version: String,
+    /// Log directory.
+    #[serde(deserialize_with = "lenient::string")]
+    pub log_directory: String,
+    /// Is log directory writable?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub log_directory_writable: bool,
+    /// WordPress version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub wp_version: String,
+    /// Is WordPress multisite?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub wp_multisite: bool,
+    /// WordPress memory limit, in bytes.
+    #[serde(deserialize_with = "lenient::number")]
+    pub wp_memory_limit: i64,
+    /// Is WordPress debug mode active?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub wp_debug_mode: bool,
+    /// Are WordPress cron jobs enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub wp_cron: bool,
+    /// WordPress language.
+    #[serde(deserialize_with = "lenient::string")]
+    pub language: String,
+    /// Server info.
+    #[serde(deserialize_with = "lenient::string")]
+    pub server_info: String,
+    /// PHP version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub php_version: String,
+    /// PHP post max size, in bytes.
+    #[serde(deserialize_with = "lenient::number")]
+    pub php_post_max_size: i64,
+    /// PHP max execution time, in seconds.
+    #[serde(deserialize_with = "lenient::number")]
+    pub php_max_execution_time: i64,
+    /// PHP max input vars.
+    #[serde(deserialize_with = "lenient::number")]
+    pub php_max_input_vars: i64,
+    /// cURL version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub curl_version: String,
+    /// Is SUHOSIN installed?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub suhosin_installed: bool,
+    /// Max upload size, in bytes.
+    #[serde(deserialize_with = "lenient::number")]
+    pub max_upload_size: i64,
+    /// MySQL version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub mysql_version: String,
+    /// MySQL version string.
+    #[serde(deserialize_with = "lenient::string")]
+    pub mysql_version_string: String,
+    /// Default timezone.
+    #[serde(deserialize_with = "lenient::string")]
+    pub default_timezone: String,
+    /// Is fsockopen/cURL enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub fsockopen_or_curl_enabled: bool,
+    /// Is SoapClient class enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub soapclient_enabled: bool,
+    /// Is DomDocument class enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub domdocument_enabled: bool,
+    /// Is GZip enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub gzip_enabled: bool,
+    /// Is mbstring enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub mbstring_enabled: bool,
+    /// Remote POST successful?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub remote_post_successful: bool,
+    /// Remote POST response.
+    #[serde(deserialize_with = "lenient::string")]
+    pub remote_post_response: String,
+    /// Remote GET successful?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub remote_get_successful: bool,
+    /// Remote GET response.
+    #[serde(deserialize_with = "lenient::string")]
+    pub remote_get_response: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Database {
+    /// WooCommerce database version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub wc_database_version: String,
+    /// Database prefix.
+    #[serde(deserialize_with = "lenient::string")]
+    pub database_prefix: String,
+    /// MaxMind GeoIP database.
+    #[serde(deserialize_with = "lenient::string")]
+    pub maxmind_geoip_database: String,
+    /// Database tables, grouped by `woocommerce` and `other`.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub database_tables: BTreeMap<String, BTreeMap<String, DatabaseTable>>,
+    /// Database size.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub database_size: DatabaseSize,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DatabaseTable {
+    /// Data size, in megabytes.
+    #[serde(deserialize_with = "lenient::string")]
+    pub data: String,
+    /// Index size, in megabytes.
+    #[serde(deserialize_with = "lenient::string")]
+    pub index: String,
+    /// Storage engine.
+    #[serde(deserialize_with = "lenient::string")]
+    pub engine: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DatabaseSize {
+    /// Data size, in megabytes.
+    #[serde(deserialize_with = "lenient::number")]
+    pub data: f64,
+    /// Index size, in megabytes.
+    #[serde(deserialize_with = "lenient::number")]
+    pub index: f64,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Plugin {
+    /// Plugin file, e.g. `woocommerce/woocommerce.php`.
+    #[serde(deserialize_with = "lenient::string")]
+    pub plugin: String,
+    /// Plugin name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub name: String,
+    /// Installed version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub version: String,
+    /// Latest available version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub version_latest: String,
+    /// Plugin URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub url: String,
+    /// Author name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub author_name: String,
+    /// Author URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub author_url: String,
+    /// Is the plugin network activated?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub network_activated: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DropinsMuPlugins {
+    /// Drop-in plugins.
+    #[serde(deserialize_with = "lenient::vec")]
+    pub dropins: Vec<Plugin>,
+    /// Must-use plugins.
+    #[serde(deserialize_with = "lenient::vec")]
+    pub mu_plugins: Vec<Plugin>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Theme {
+    /// Theme name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub name: String,
+    /// Theme version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub version: String,
+    /// Latest version of theme.
+    #[serde(deserialize_with = "lenient::string")]
+    pub version_latest: String,
+    /// Theme author URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub author_url: String,
+    /// Is this theme a child theme?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub is_child_theme: bool,
+    /// Does the theme declare WooCommerce support?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub has_woocommerce_support: bool,
+    /// Does the theme have a woocommerce.php file?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub has_woocommerce_file: bool,
+    /// Does this theme have outdated templates?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub has_outdated_templates: bool,
+    /// Template overrides.
+    #[serde(deserialize_with = "lenient::vec")]
+    pub overrides: Vec<serde_json::Value>,
+    /// Parent theme name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub parent_name: String,
+    /// Parent theme version.
+    #[serde(deserialize_with = "lenient::string")]
+    pub parent_version: String,
+    /// Parent theme author URL.
+    #[serde(deserialize_with = "lenient::string")]
+    pub parent_author_url: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SystemSettings {
+    /// REST API enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub api_enabled: bool,
+    /// SSL forced?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub force_ssl: bool,
+    /// Currency.
+    #[serde(deserialize_with = "lenient::string")]
+    pub currency: String,
+    /// Currency symbol.
+    #[serde(deserialize_with = "lenient::string")]
+    pub currency_symbol: String,
+    /// Currency position.
+    #[serde(deserialize_with = "lenient::string")]
+    pub currency_position: String,
+    /// Thousand separator.
+    #[serde(deserialize_with = "lenient::string")]
+    pub thousand_separator: String,
+    /// Decimal separator.
+    #[serde(deserialize_with = "lenient::string")]
+    pub decimal_separator: String,
+    /// Number of decimals.
+    #[serde(deserialize_with = "lenient::number")]
+    pub number_of_decimals: i32,
+    /// Geolocation enabled?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub geolocation_enabled: bool,
+    /// Taxonomy terms for product/order statuses.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub taxonomies: BTreeMap<String, String>,
+    /// Terms in the product visibility taxonomy.
+    #[serde(deserialize_with = "lenient::or_default")]
+    pub product_visibility_terms: BTreeMap<String, String>,
+    /// Is the store connected to WooCommerce.com?
+    #[serde(deserialize_with = "lenient::string")]
+    pub woocommerce_com_connected: String,
+    /// Are approved download directories enforced?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub enforce_approved_download_dirs: bool,
+    /// Order datastore class.
+    #[serde(deserialize_with = "lenient::string")]
+    pub order_datastore: String,
+    /// Are orders stored in custom tables (HPOS)?
+    #[serde(rename = "HPOS_enabled")]
+    #[serde(deserialize_with = "lenient::bool")]
+    pub hpos_enabled: bool,
+    /// Is the HPOS feature screen enabled?
+    #[serde(rename = "HPOS_feature_screen_enabled")]
+    #[serde(deserialize_with = "lenient::bool")]
+    pub hpos_feature_screen_enabled: bool,
+    /// Is sync between posts and custom order tables enabled?
+    #[serde(rename = "HPOS_sync_enabled")]
+    #[serde(deserialize_with = "lenient::bool")]
+    pub hpos_sync_enabled: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Security {
+    /// Is the connection to your store secure?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub secure_connection: bool,
+    /// Hide errors from visitors?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub hide_errors: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Page {
+    /// Page name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub page_name: String,
+    /// Page ID.
+    #[serde(deserialize_with = "lenient::string")]
+    pub page_id: String,
+    /// Page set?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub page_set: bool,
+    /// Page exists?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub page_exists: bool,
+    /// Page visible?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub page_visible: bool,
+    /// Shortcode the page should contain.
+    #[serde(deserialize_with = "lenient::string")]
+    pub shortcode: String,
+    /// Block the page should contain.
+    #[serde(deserialize_with = "lenient::string")]
+    pub block: String,
+    /// Is the shortcode required?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub shortcode_required: bool,
+    /// Is the shortcode present?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub shortcode_present: bool,
+    /// Is the block present?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub block_present: bool,
+    /// Is the block required?
+    #[serde(deserialize_with = "lenient::bool")]
+    pub block_required: bool,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PostTypeCount {
+    /// Post type.
+    #[serde(rename = "type")]
+    #[serde(deserialize_with = "lenient::string")]
+    pub post_type: String,
+    /// Number of posts.
+    #[serde(deserialize_with = "lenient::number")]
+    pub count: i64,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SystemStatusTool {
+    /// A unique identifier for the tool.
+    #[serde(deserialize_with = "lenient::string")]
+    pub id: String,
+    /// Tool name.
+    #[serde(deserialize_with = "lenient::string")]
+    pub name: String,
+    /// What running the tool will do.
+    #[serde(deserialize_with = "lenient::string")]
+    pub action: String,
+    /// Tool description.
+    #[serde(deserialize_with = "lenient::string")]
+    pub description: String,
+    /// Did the tool run successfully?
+    pub success: Option<bool>,
+    /// Tool return message.
+    #[serde(deserialize_with = "lenient::option")]
+    pub message: Option<String>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+impl Entity for SystemStatusTool {
+    fn endpoint() -> String {
+        String::from("system_status/tools/")
+    }
+
+    fn child_endpoint(parent_id: i32) -> String {
+        let _ = parent_id;
+        String::new()
+    }
+}
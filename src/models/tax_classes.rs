@@ -1,13 +1,20 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::tax_classes::TaxClassCreateBuilder;
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct TaxClass {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::string")]
     pub slug: String,
     /// Tax class name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for TaxClass {
     fn endpoint() -> String {
@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    customers::{Billing, Shipping},
+    location::postcode_matches,
+    store_settings::StoreAddress,
+    tax_rates::TaxRate,
+};
+
+/// Offline matching of tax rates to an address, the way WooCommerce does it.
+///
+/// # Example
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use rust_woocommerce::{ApiClient, Config, Customer, TaxEngine, TaxLocation, TaxRate};
+/// use tracing::info;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     tracing_subscriber::fmt::init();
+///     let config = Config::new("woo.toml")?;
+///     let client = ApiClient::new(&config)?;
+///     let engine = TaxEngine::new(client.list_all::<TaxRate>().await?);
+///     let customer = client.retrieve::<Customer>(25).await?;
+///     let location = TaxLocation::from(&customer.shipping);
+///     let tax = engine.line_tax(&location, "", 19.99, 2, false);
+///     info!("Tax {} on {}", tax.total_tax, tax.total);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TaxEngine {
+    rates: Vec<TaxRate>,
+    base_location: Option<TaxLocation>,
+}
+/// Address tax rates are matched against.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TaxLocation {
+    /// ISO code of the country.
+    pub country: String,
+    /// ISO code of the state.
+    pub state: String,
+    /// Postal code.
+    pub postcode: String,
+    /// City name.
+    pub city: String,
+}
+/// Tax of one line.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LineTax {
+    /// Line total, excluding tax.
+    pub total: f64,
+    /// Line tax.
+    pub total_tax: f64,
+    /// Taxes by tax rate ID.
+    pub taxes: BTreeMap<i32, f64>,
+}
+impl TaxLocation {
+    pub fn new(
+        country: impl Into<String>,
+        state: impl Into<String>,
+        postcode: impl Into<String>,
+        city: impl Into<String>,
+    ) -> Self {
+        TaxLocation {
+            country: country.into(),
+            state: state.into(),
+            postcode: postcode.into(),
+            city: city.into(),
+        }
+    }
+}
+impl From<&Billing> for TaxLocation {
+    fn from(value: &Billing) -> Self {
+        TaxLocation::new(&value.country, &value.state, &value.postcode, &value.city)
+    }
+}
+impl From<&Shipping> for TaxLocation {
+    fn from(value: &Shipping) -> Self {
+        TaxLocation::new(&value.country, &value.state, &value.postcode, &value.city)
+    }
+}
+impl From<&StoreAddress> for TaxLocation {
+    fn from(value: &StoreAddress) -> Self {
+        TaxLocation::new(&value.country, &value.state, &value.postcode, &value.city)
+    }
+}
+impl TaxEngine {
+    pub fn new(rates: Vec<TaxRate>) -> Self {
+        TaxEngine {
+            rates,
+            base_location: None,
+        }
+    }
+    /// Store base location. With prices entered with tax, customers elsewhere pay
+    /// the price without the base taxes plus their own taxes, like WooCommerce does by default.
+    pub fn base_location(mut self, location: TaxLocation) -> Self {
+        let _ = self.base_location.insert(location);
+        self
+    }
+    /// Rates of the tax class that apply to the location, one per priority,
+    /// sorted by priority. An empty class is the standard class.
+    pub fn rates(&self, location: &TaxLocation, tax_class: &str) -> Vec<&TaxRate> {
+        matching_rates(&self.rates, Some(location), tax_class, false)
+    }
+    /// Rates of the tax class that apply to shipping to the location.
+    pub fn shipping_rates(&self, location: &TaxLocation, tax_class: &str) -> Vec<&TaxRate> {
+        matching_rates(&self.rates, Some(location), tax_class, true)
+    }
+    /// Tax of a line with a unit price, not rounded.
+    /// With `prices_include_tax` the tax is taken out of the price.
+    pub fn line_tax(
+        &self,
+        location: &TaxLocation,
+        tax_class: &str,
+        price: f64,
+        quantity: i32,
+        prices_include_tax: bool,
+    ) -> LineTax {
+        let rates = self.rates(location, tax_class);
+        let mut line_price = price * quantity as f64;
+        if prices_include_tax {
+            if let Some(base) = &self.base_location {
+                let base_rates = self.rates(base, tax_class);
+                if rate_ids(&base_rates) != rate_ids(&rates) {
+                    let base_tax = TaxEngine::calc_tax(line_price, &base_rates, true)
+                        .values()
+                        .sum::<f64>();
+                    let net = line_price - base_tax;
+                    let taxes = TaxEngine::calc_tax(net, &rates, false);
+                    line_price = net + taxes.values().sum::<f64>();
+                }
+            }
+        }
+        let taxes = TaxEngine::calc_tax(line_price, &rates, prices_include_tax);
+        let total_tax = taxes.values().sum::<f64>();
+        LineTax {
+            total: match prices_include_tax {
+                true => line_price - total_tax,
+                false => line_price,
+            },
+            total_tax,
+            taxes,
+        }
+    }
+    /// WooCommerce `WC_Tax::calc_tax`: taxes of a price by rate ID, not rounded.
+    /// Compound rates apply on top of the other taxes.
+    pub fn calc_tax(price: f64, rates: &[&TaxRate], inclusive: bool) -> BTreeMap<i32, f64> {
+        let rate = |r: &TaxRate| r.rate.trim().parse::<f64>().unwrap_or_default();
+        let mut taxes = BTreeMap::new();
+        let (compound, regular): (Vec<&TaxRate>, Vec<&TaxRate>) =
+            rates.iter().copied().partition(|r| r.compound);
+        if inclusive {
+            let mut non_compound_price = price;
+            for r in compound.iter().rev() {
+                let tax = non_compound_price - non_compound_price / (1.0 + rate(r) / 100.0);
+                taxes.insert(r.id, tax);
+                non_compound_price -= tax;
+            }
+            let regular_rate = 1.0 + regular.iter().map(|r| rate(r)).sum::<f64>() / 100.0;
+            for r in regular {
+                taxes.insert(r.id, rate(r) / 100.0 / regular_rate * non_compound_price);
+            }
+        } else {
+            for r in regular {
+                taxes.insert(r.id, price * rate(r) / 100.0);
+            }
+            let pre_compound_total = taxes.values().sum::<f64>();
+            for r in compound {
+                taxes.insert(r.id, (price + pre_compound_total) * rate(r) / 100.0);
+            }
+        }
+        taxes
+    }
+}
+/// Rates of a tax class matching the location, if any, one per priority.
+/// WooCommerce uses the first rate by order of every priority.
+pub(crate) fn matching_rates<'a>(
+    rates: &'a [TaxRate],
+    location: Option<&TaxLocation>,
+    tax_class: &str,
+    shipping: bool,
+) -> Vec<&'a TaxRate> {
+    let mut matched = rates
+        .iter()
+        .filter(|r| same_tax_class(&r.class, tax_class))
+        .filter(|r| !shipping || r.shipping)
+        .filter(|r| location.map_or(true, |location| rate_matches(r, location)))
+        .collect::<Vec<_>>();
+    matched.sort_by_key(|r| (r.priority, r.order));
+    matched.dedup_by_key(|r| r.priority);
+    matched
+}
+/// Empty country, state, postcodes or cities match any value.
+fn rate_matches(rate: &TaxRate, location: &TaxLocation) -> bool {
+    let postcodes = split(&rate.postcode, &rate.postcodes);
+    let cities = split(&rate.city, &rate.cities);
+    (rate.country.is_empty() || rate.country.eq_ignore_ascii_case(&location.country))
+        && (rate.state.is_empty() || rate.state.eq_ignore_ascii_case(&location.state))
+        && (postcodes.is_empty()
+            || postcodes
+                .iter()
+                .any(|p| postcode_matches(p, &location.postcode)))
+        && (cities.is_empty()
+            || cities
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(location.city.trim())))
+}
+/// The single value fields hold `;` separated lists in older WooCommerce versions.
+fn split<'a>(single: &'a str, list: &'a [String]) -> Vec<&'a str> {
+    single
+        .split(';')
+        .chain(list.iter().map(String::as_str))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+fn rate_ids(rates: &[&TaxRate]) -> Vec<i32> {
+    rates.iter().map(|r| r.id).collect()
+}
+/// Tax rates use `standard` for the standard class, order lines an empty string.
+pub(crate) fn same_tax_class(a: &str, b: &str) -> bool {
+    let class = |value: &str| match value {
+        "" | "standard" => String::from("standard"),
+        other => other.to_lowercase().replace(' ', "-"),
+    };
+    class(a) == class(b)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(id: i32, rate: &str, priority: i32, compound: bool) -> TaxRate {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "country": "US",
+            "rate": rate,
+            "priority": priority,
+            "compound": compound,
+            "order": id,
+            "class": "standard",
+        }))
+        .unwrap()
+    }
+    fn us(postcode: &str) -> TaxLocation {
+        TaxLocation::new("US", "CA", postcode, "Beverly Hills")
+    }
+    #[test]
+    fn compound_rates_apply_on_regular_taxes() {
+        let rates = [
+            rate(1, "10", 1, false),
+            rate(2, "5", 2, true),
+            rate(3, "5", 3, true),
+        ];
+        let taxes = TaxEngine::calc_tax(100.0, &rates.iter().collect::<Vec<_>>(), false);
+        assert!((taxes[&1] - 10.0).abs() < 1e-9);
+        assert!((taxes[&2] - 5.5).abs() < 1e-9);
+        assert!((taxes[&3] - 5.5).abs() < 1e-9);
+    }
+    #[test]
+    fn inclusive_prices() {
+        let regular = rate(1, "20", 1, false);
+        let taxes = TaxEngine::calc_tax(120.0, &[&regular], true);
+        assert!((taxes[&1] - 20.0).abs() < 1e-9);
+        let regular = rate(1, "10", 1, false);
+        let compound = rate(2, "10", 2, true);
+        let taxes = TaxEngine::calc_tax(121.0, &[&regular, &compound], true);
+        assert!((taxes[&1] - 10.0).abs() < 1e-9);
+        assert!((taxes[&2] - 11.0).abs() < 1e-9);
+        let engine = TaxEngine::new(vec![rate(1, "20", 1, false)]);
+        let tax = engine.line_tax(&us("90210"), "", 12.0, 2, true);
+        assert!((tax.total - 20.0).abs() < 1e-9);
+        assert!((tax.total_tax - 4.0).abs() < 1e-9);
+    }
+    #[test]
+    fn one_rate_per_priority() {
+        let engine = TaxEngine::new(vec![
+            rate(3, "5", 2, false),
+            rate(2, "8", 1, false),
+            rate(1, "7", 1, false),
+        ]);
+        let ids = rate_ids(&engine.rates(&us("90210"), ""));
+        assert_eq!(ids, [1, 3]);
+    }
+    #[test]
+    fn postcode_ranges_and_wildcards() {
+        let mut range = rate(1, "9.5", 1, false);
+        range.postcodes = vec![String::from("90210...90299")];
+        let mut wildcard = rate(2, "1", 2, false);
+        wildcard.postcode = String::from("10001;902*");
+        let engine = TaxEngine::new(vec![range, wildcard]);
+        assert_eq!(rate_ids(&engine.rates(&us("90250"), "")), [1, 2]);
+        assert!(engine.rates(&us("90300"), "").is_empty());
+        assert_eq!(rate_ids(&engine.rates(&us("90299"), "")), [1, 2]);
+        assert_eq!(rate_ids(&engine.rates(&us("10001"), "")), [2]);
+        assert!(engine.rates(&us("60601"), "").is_empty());
+        assert!(engine
+            .rates(&TaxLocation::new("CA", "", "90250", ""), "")
+            .is_empty());
+    }
+    #[test]
+    fn standard_class_names() {
+        assert!(same_tax_class("standard", ""));
+        assert!(same_tax_class("reduced-rate", "Reduced rate"));
+        assert!(!same_tax_class("zero-rate", ""));
+    }
+}
@@ -1,37 +1,56 @@
+use super::lenient;
 use crate::controllers::Entity;
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::tax_rates::{TaxRateCreateBuilder, TaxRateUpdateBuilder};
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct TaxRate {
     /// Unique identifier for the resource.READ-ONLY
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// Country ISO 3166 code.
+    #[serde(deserialize_with = "lenient::string")]
     pub country: String,
     /// State code.
+    #[serde(deserialize_with = "lenient::string")]
     pub state: String,
     /// Postcode/ZIP, it doesn't support multiple values. Deprecated as of WooCommerce 5.3, postcodes should be used instead.
+    #[serde(deserialize_with = "lenient::string")]
     pub postcode: String,
     /// City name, it doesn't support multiple values. Deprecated as of WooCommerce 5.3, postcodes should be used instead.
+    #[serde(deserialize_with = "lenient::string")]
     pub city: String,
     /// Postcodes/ZIPs.
+    #[serde(deserialize_with = "lenient::vec")]
     pub postcodes: Vec<String>,
     /// City names.
+    #[serde(deserialize_with = "lenient::vec")]
     pub cities: Vec<String>,
     /// Tax rate.
+    #[serde(deserialize_with = "lenient::string")]
     pub rate: String,
     /// Tax rate name.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Tax priority. Only 1 matching rate per priority will be used. To define multiple tax rates for a single area you need to specify a different priority per rate. Default is 1.
+    #[serde(deserialize_with = "lenient::number")]
     pub priority: i32,
     /// Whether this is a compound rate. Compound tax rates are applied on top of other tax rates. Default is false.
+    #[serde(deserialize_with = "lenient::bool")]
     pub compound: bool,
     /// Whether this tax rate also gets applied to shipping. Default is true.
+    #[serde(deserialize_with = "lenient::bool")]
     pub shipping: bool,
     /// Indicates the order that will appear in queries.
+    #[serde(deserialize_with = "lenient::number")]
     pub order: i32,
     /// Tax class. Default is standard.
+    #[serde(deserialize_with = "lenient::string")]
     pub class: String,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for TaxRate {
     fn endpoint() -> String {
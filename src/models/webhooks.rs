@@ -1,38 +1,56 @@
 use std::fmt::Display;
 
+use super::lenient;
 use crate::controllers::Entity;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::controllers::webhooks::{WebhookCreateBuilder, WebhookUpdateBuilder};
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Webhook {
     /// Unique identifier for the resource.
+    #[serde(deserialize_with = "lenient::number")]
     pub id: i32,
     /// A friendly name for the webhook.
+    #[serde(deserialize_with = "lenient::string")]
     pub name: String,
     /// Webhook status. Options: active, paused and disabled. Default is active.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub status: WebhookStatus,
     /// Webhook topic.
+    #[serde(deserialize_with = "lenient::string")]
     pub topic: String,
     /// Webhook resource.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub resource: Resource,
     /// Webhook event.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub event: Event,
     /// WooCommerce action names associated with the webhook.
+    #[serde(deserialize_with = "lenient::vec")]
     pub hooks: Vec<String>,
     /// The URL where the webhook payload is delivered.
+    #[serde(deserialize_with = "lenient::string")]
     pub delivery_url: String,
     /// Secret key used to generate a hash of the delivered webhook and provided in the request headers. This will default is a MD5 hash from the current user's ID
+    #[serde(deserialize_with = "lenient::option")]
     pub secret: Option<String>,
     /// The date the webhook was created, in the site's timezone.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created: NaiveDateTime,
     /// The date the webhook was created, as GMT.
+    #[serde(deserialize_with = "lenient::or_default")]
     pub date_created_gmt: NaiveDateTime,
     /// The date the webhook was last modified, in the site's timezone.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_modified: Option<NaiveDateTime>,
     /// The date the webhook was last modified, as GMT.
+    #[serde(deserialize_with = "lenient::option")]
     pub date_modified_gmt: Option<NaiveDateTime>,
+    /// Fields not covered by the model, e.g. added by plugins.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 impl Entity for Webhook {
     fn endpoint() -> String {
@@ -57,21 +75,37 @@ impl Webhook {
     pub fn update() -> WebhookUpdateBuilder {
         WebhookUpdateBuilder::default()
     }
+    /// The date the webhook was created, as UTC.
+    pub fn date_created_utc(&self) -> DateTime<Utc> {
+        self.date_created_gmt.and_utc()
+    }
+    /// The date the webhook was last modified, as UTC.
+    pub fn date_modified_utc(&self) -> Option<DateTime<Utc>> {
+        self.date_modified_gmt.map(|d| d.and_utc())
+    }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum WebhookStatus {
+    #[default]
     Active,
     Paused,
     Disabled,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Resource {
     Coupon,
     Customer,
     Order,
+    #[default]
     Product,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 impl Display for Resource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -80,16 +114,21 @@ impl Display for Resource {
             Resource::Customer => write!(f, "customer"),
             Resource::Order => write!(f, "order"),
             Resource::Product => write!(f, "product"),
+            Resource::Other(resource) => write!(f, "{resource}"),
         }
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Event {
+    #[default]
     Created,
     Updated,
     Deleted,
     Restored,
+    /// Any other value, e.g. added by a plugin.
+    #[serde(untagged)]
+    Other(String),
 }
 impl Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -98,6 +137,7 @@ impl Display for Event {
             Event::Updated => write!(f, "updated"),
             Event::Deleted => write!(f, "deleted"),
             Event::Restored => write!(f, "restored"),
+            Event::Other(event) => write!(f, "{event}"),
         }
     }
 }